@@ -1,7 +1,52 @@
-use crate::models::{Config, Meeting, Role};
-use chrono::{DateTime, Local};
+use anyhow::Result;
+
+use crate::models::{Config, Meeting, Participant, Role};
+use crate::money::Money;
+use chrono::{DateTime, Duration, Local};
 use std::collections::HashMap;
 
+/// A meeting's cost split into its parts.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostBreakdown {
+    /// Attendees' hourly rates for the meeting's length.
+    pub base: Money,
+    /// Benefits and overhead on top of `base`.
+    pub overhead: Money,
+    /// The fixed focus time each attendee loses to the meeting.
+    pub context_switch: Money,
+}
+
+impl CostBreakdown {
+    pub fn total(&self) -> Money {
+        self.base + self.overhead + self.context_switch
+    }
+}
+
+/// Shorter lengths `estimate` compares a meeting against.
+pub const SHORTER_VARIANTS: [i64; 2] = [25, 50];
+
+/// Projected cost of a meeting that hasn't happened yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub cost: Money,
+    pub breakdown: CostBreakdown,
+    pub cost_per_minute: Money,
+    /// Head count and cost per role, most expensive first.
+    pub by_role: Vec<(Role, u32, Money)>,
+    /// The same meeting with one thing changed, in the order shown.
+    pub variants: Vec<(Variant, Money)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Variant {
+    /// Shortened to this many minutes.
+    Minutes(i64),
+    /// With one fewer attendee of this role.
+    OneFewer(Role),
+}
+
+/// Prices meetings in the configured display currency. Roles paid in other
+/// currencies are converted through the config's exchange rates.
 pub struct Calculator<'a> {
     config: &'a Config,
 }
@@ -17,64 +62,149 @@ impl<'a> Calculator<'a> {
         start_time: DateTime<Local>,
         end_time: DateTime<Local>,
         attendees: &HashMap<Role, u32>,
-    ) -> f64 {
-        let duration_minutes = (end_time - start_time).num_minutes();
-        if duration_minutes <= 0 {
-            return 0.0;
+    ) -> Result<Money> {
+        Ok(self.breakdown(start_time, end_time, attendees)?.total())
+    }
+
+    /// Itemized cost for a meeting given start and end times, with every
+    /// attendee there throughout.
+    pub fn breakdown(
+        &self,
+        start_time: DateTime<Local>,
+        end_time: DateTime<Local>,
+        attendees: &HashMap<Role, u32>,
+    ) -> Result<CostBreakdown> {
+        self.meeting_breakdown(&Meeting::new(start_time, end_time, attendees.clone(), Money::ZERO))
+    }
+
+    /// What `meeting` costs given its times, attendees, named people and
+    /// attendance, regardless of the cost stored on it.
+    pub fn meeting_cost(&self, meeting: &Meeting) -> Result<Money> {
+        Ok(self.meeting_breakdown(meeting)?.total())
+    }
+
+    /// Itemized cost of `meeting`.
+    ///
+    /// Each attendee is charged the rate in force when the meeting started,
+    /// or a named person their own rate, for the time they were there while
+    /// the meter wasn't paused. That time is measured to the second and then
+    /// rounded by the configured `Rounding` policy. The context-switch part
+    /// is charged in full for everyone who was billed any time.
+    ///
+    /// Fails if an attendee's role is no longer in the config.
+    pub fn meeting_breakdown(&self, meeting: &Meeting) -> Result<CostBreakdown> {
+        let rounding = self.config.rounding;
+        if rounding.billed_minutes(meeting.billed_duration()) <= 0.0 {
+            return Ok(CostBreakdown::default());
         }
-        let hours = duration_minutes as f64 / 60.0;
 
-        let base_cost: f64 = attendees
-            .iter()
-            .map(|(role, count)| {
-                let rate = self.config.get_rate(role);
-                rate * (*count as f64) * hours
-            })
-            .sum();
+        let mut breakdown = CostBreakdown::default();
 
-        // Apply multipliers
-        let mut total = base_cost;
+        for presence in meeting.presence() {
+            let minutes = rounding.billed_minutes(presence.duration());
+            if presence.heads == 0 || minutes <= 0.0 {
+                continue;
+            }
+            let rate = match presence.person {
+                Some(participant) => self.config.participant_rate_at(participant, meeting.start_time)?,
+                None => self.config.rate_at(presence.role, meeting.start_time)?,
+            };
+            let overhead = self.config.overhead_percent(presence.role) / 100.0;
+            let heads = presence.heads as f64;
 
-        if self.config.include_benefits {
-            total *= 1.4; // 40% overhead for benefits
+            let base = rate.mul_f64(heads * minutes / 60.0);
+            breakdown.base += base;
+            breakdown.overhead += base.mul_f64(overhead);
+            // Lost focus time is valued like meeting time, overhead included.
+            breakdown.context_switch += rate
+                .mul_f64((1.0 + overhead) * heads * self.config.context_switch_minutes / 60.0);
         }
 
-        total *= self.config.context_switch_multiplier;
+        Ok(breakdown)
+    }
+
+    /// Calculate cost per minute for real-time display, for the `attendees`
+    /// and named `people` in the room.
+    pub fn cost_per_minute(
+        &self,
+        attendees: &HashMap<Role, u32>,
+        people: &[Participant],
+    ) -> Result<Money> {
+        let now = Local::now();
+        let mut meeting = Meeting::new(now, now, attendees.clone(), Money::ZERO);
+        meeting.people = people.to_vec();
 
-        total
+        let mut per_hour = Money::ZERO;
+        for presence in meeting.presence() {
+            let rate = match presence.person {
+                Some(participant) => self.config.loaded_participant_rate_at(participant, now)?,
+                None => self.config.loaded_rate_at(presence.role, now)?,
+            };
+            per_hour += rate.mul_f64(presence.heads as f64);
+        }
+        Ok(per_hour.div_round(60))
     }
 
-    /// Calculate cost per minute for real-time display
-    pub fn cost_per_minute(&self, attendees: &HashMap<Role, u32>) -> f64 {
-        let cost_per_hour: f64 = attendees
+    /// Prices a meeting of `duration` starting at `start`, along with what it
+    /// would cost shortened to 25 or 50 minutes or with one fewer attendee of
+    /// each role.
+    pub fn estimate(
+        &self,
+        start: DateTime<Local>,
+        duration: Duration,
+        attendees: &HashMap<Role, u32>,
+    ) -> Result<Estimate> {
+        let end = start + duration;
+        let breakdown = self.breakdown(start, end, attendees)?;
+        let cost = breakdown.total();
+
+        let mut by_role: Vec<(Role, u32, Money)> = attendees
             .iter()
+            .filter(|(_, count)| **count > 0)
             .map(|(role, count)| {
-                let rate = self.config.get_rate(role);
-                rate * (*count as f64)
+                let alone = HashMap::from([(role.clone(), *count)]);
+                Ok((role.clone(), *count, self.calculate_cost(start, end, &alone)?))
             })
-            .sum();
-
-        let mut cost = cost_per_hour / 60.0;
+            .collect::<Result<_>>()?;
+        by_role.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
 
-        if self.config.include_benefits {
-            cost *= 1.4;
+        let mut variants = Vec::new();
+        for minutes in SHORTER_VARIANTS {
+            if Duration::minutes(minutes) < duration {
+                let shorter = self.calculate_cost(start, start + Duration::minutes(minutes), attendees)?;
+                variants.push((Variant::Minutes(minutes), shorter));
+            }
+        }
+        for (role, _, _) in &by_role {
+            let mut fewer = attendees.clone();
+            if let Some(count) = fewer.get_mut(role) {
+                *count -= 1;
+            }
+            variants.push((Variant::OneFewer(role.clone()), self.calculate_cost(start, end, &fewer)?));
         }
 
-        cost *= self.config.context_switch_multiplier;
-
-        cost
+        Ok(Estimate {
+            cost,
+            breakdown,
+            cost_per_minute: self.cost_per_minute(attendees, &[])?,
+            by_role,
+            variants,
+        })
     }
 
-    pub fn update_meeting_cost(&self, meeting: &mut Meeting) {
-        meeting.cost =
-            self.calculate_cost(meeting.start_time, meeting.end_time, &meeting.attendees);
+    /// Recalculates `meeting.cost` from its current times and attendees, in
+    /// the display currency.
+    pub fn update_meeting_cost(&self, meeting: &mut Meeting) -> Result<()> {
+        meeting.cost = self.meeting_cost(meeting)?;
+        meeting.currency = self.config.currency.clone();
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Duration;
+    use crate::models::Rounding;
 
     #[test]
     fn test_calculate_cost() {
@@ -82,8 +212,8 @@ mod tests {
         let calc = Calculator::new(&config);
 
         let mut attendees = HashMap::new();
-        attendees.insert(Role::Engineer, 3);
-        attendees.insert(Role::Director, 1);
+        attendees.insert(Role::new("engineer"), 3);
+        attendees.insert(Role::new("director"), 1);
 
         let start_time = Local::now();
         let end_time_60 = start_time + Duration::minutes(60);
@@ -91,12 +221,232 @@ mod tests {
 
         // 3 engineers at $43/hr + 1 director at $105/hr = $234/hr
         // For 60 minutes = $234
-        let cost = calc.calculate_cost(start_time, end_time_60, &attendees);
-        assert_eq!(cost, 234.0);
+        let cost = calc.calculate_cost(start_time, end_time_60, &attendees).unwrap();
+        assert_eq!(cost, Money::dollars(234));
 
         // For 30 minutes = $117
-        let cost = calc.calculate_cost(start_time, end_time_30, &attendees);
-        assert_eq!(cost, 117.0);
+        let cost = calc.calculate_cost(start_time, end_time_30, &attendees).unwrap();
+        assert_eq!(cost, Money::dollars(117));
+    }
+
+    #[test]
+    fn test_overhead_and_context_switch() {
+        let mut config = Config {
+            overhead_percent: 40.0,
+            context_switch_minutes: 15.0,
+            ..Default::default()
+        };
+        config.roles.iter_mut().find(|def| def.id == Role::new("director")).unwrap().overhead_percent =
+            Some(20.0);
+        let calc = Calculator::new(&config);
+
+        let mut attendees = HashMap::new();
+        attendees.insert(Role::new("engineer"), 3);
+        attendees.insert(Role::new("director"), 1);
+
+        let start_time = Local::now();
+        let breakdown = calc.breakdown(start_time, start_time + Duration::minutes(60), &attendees).unwrap();
+
+        // Base: 3 x $43 + $105 = $234.
+        assert_eq!(breakdown.base, Money::dollars(234));
+        // Overhead: 40% of $129 + 20% of $105.
+        assert_eq!(breakdown.overhead, Money::from_f64(51.6 + 21.0));
+        // A quarter hour per attendee at the loaded rates.
+        assert_eq!(breakdown.context_switch, Money::from_f64((180.6 + 126.0) / 4.0));
+        assert_eq!(
+            calc.calculate_cost(start_time, start_time + Duration::minutes(60), &attendees).unwrap(),
+            breakdown.total()
+        );
+
+        // Zero-length meetings cost nothing, context switch included.
+        assert_eq!(calc.calculate_cost(start_time, start_time, &attendees).unwrap(), Money::ZERO);
+        assert_eq!(calc.cost_per_minute(&attendees, &[]).unwrap(), Money::from_f64((180.6 + 126.0) / 60.0));
+    }
+
+    #[test]
+    fn test_seconds_and_rounding() {
+        let mut attendees = HashMap::new();
+        attendees.insert(Role::new("engineer"), 2);
+        let start_time = Local::now();
+        let cost_after = |config: &Config, seconds: i64| {
+            Calculator::new(config)
+                .calculate_cost(start_time, start_time + Duration::seconds(seconds), &attendees)
+                .unwrap()
+        };
+
+        // 2 engineers at $43/hr = $86/hr.
+        let exact = Config::default();
+        assert_eq!(cost_after(&exact, 59), Money::from_f64(86.0 * 59.0 / 3600.0));
+        assert_eq!(cost_after(&exact, 90), Money::from_f64(2.15));
+
+        let nearest = Config {
+            rounding: Rounding::NearestMinute,
+            ..Default::default()
+        };
+        assert_eq!(cost_after(&nearest, 89), Money::from_f64(86.0 / 60.0));
+        assert_eq!(cost_after(&nearest, 90), Money::from_f64(86.0 * 2.0 / 60.0));
+        assert_eq!(cost_after(&nearest, 29), Money::ZERO);
+
+        let blocks = Config {
+            rounding: Rounding::QuarterHour,
+            ..Default::default()
+        };
+        assert_eq!(cost_after(&blocks, 1), Money::from_f64(21.5));
+        assert_eq!(cost_after(&blocks, 15 * 60), Money::from_f64(21.5));
+        assert_eq!(cost_after(&blocks, 15 * 60 + 1), Money::dollars(43));
+    }
+
+    #[test]
+    fn test_rate_in_force_at_start() {
+        use chrono::{NaiveDate, TimeZone};
+
+        let mut config = Config::default();
+        let engineer = Role::new("engineer");
+        config
+            .roles
+            .iter_mut()
+            .find(|def| def.id == engineer)
+            .unwrap()
+            .set_rate_from(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), Money::dollars(50), Default::default());
+        let calc = Calculator::new(&config);
+        let attendees = HashMap::from([(engineer, 2)]);
+
+        let before = Local.with_ymd_and_hms(2025, 12, 31, 23, 0, 0).unwrap();
+        let after = Local.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap();
+        let hour = Duration::hours(1);
+        assert_eq!(calc.calculate_cost(before, before + hour, &attendees).unwrap(), Money::dollars(86));
+        assert_eq!(calc.calculate_cost(after, after + hour, &attendees).unwrap(), Money::dollars(100));
+    }
+
+    #[test]
+    fn test_named_people_use_their_own_rate() {
+        use crate::models::Person;
+
+        let config = Config {
+            overhead_percent: 50.0,
+            people: vec![Person {
+                name: "Priya".to_string(),
+                role: Role::new("engineer"),
+                rate: Some(Money::dollars(100)),
+                currency: Default::default(),
+                team: None,
+            }],
+            ..Default::default()
+        };
+        let calc = Calculator::new(&config);
+        let start = Local::now();
+        let attendees = HashMap::from([(Role::new("engineer"), 2)]);
+        let people = |name: &str| {
+            vec![Participant {
+                name: name.to_string(),
+                role: Role::new("engineer"),
+                team: None,
+            }]
+        };
+        let meeting = |name: &str| {
+            let mut meeting = Meeting::new(start, start + Duration::hours(1), attendees.clone(), Money::ZERO);
+            meeting.people = people(name);
+            meeting
+        };
+
+        // One engineer at $43 plus Priya at $100, both with 50% overhead.
+        assert_eq!(calc.meeting_cost(&meeting("Priya")).unwrap(), Money::from_f64(214.5));
+        // Someone not in the directory is charged their role's rate.
+        assert_eq!(calc.meeting_cost(&meeting("Sam")).unwrap(), Money::dollars(129));
+        assert_eq!(calc.cost_per_minute(&attendees, &people("Priya")).unwrap(), Money::from_f64(3.575));
+    }
+
+    #[test]
+    fn test_partial_attendance() {
+        use crate::models::{Attendance, Person};
+
+        let config = Config {
+            people: vec![Person {
+                name: "Priya".to_string(),
+                role: Role::new("product_manager"),
+                rate: Some(Money::dollars(90)),
+                currency: Default::default(),
+                team: None,
+            }],
+            ..Default::default()
+        };
+        let calc = Calculator::new(&config);
+        let start = Local::now();
+        let at = |minutes| start + Duration::minutes(minutes);
+        let mut meeting = Meeting::new(
+            start,
+            at(60),
+            HashMap::from([(Role::new("engineer"), 3), (Role::new("product_manager"), 1)]),
+            Money::ZERO,
+        );
+        meeting.people = vec![Participant {
+            name: "Priya".to_string(),
+            role: Role::new("product_manager"),
+            team: None,
+        }];
+        meeting.attendance = vec![
+            Attendance {
+                role: Role::new("engineer"),
+                count: 2,
+                name: None,
+                joined: at(45),
+                left: at(60),
+            },
+            Attendance {
+                role: Role::new("product_manager"),
+                count: 1,
+                name: Some("Priya".to_string()),
+                joined: at(0),
+                left: at(10),
+            },
+            Attendance {
+                role: Role::new("product_manager"),
+                count: 1,
+                name: Some("Priya".to_string()),
+                joined: at(40),
+                left: at(60),
+            },
+        ];
+        meeting.check_attendance().unwrap();
+
+        // One engineer throughout ($43), two for the last quarter hour
+        // ($21.50) and Priya for half an hour ($45).
+        assert_eq!(calc.meeting_cost(&meeting).unwrap(), Money::from_f64(109.5));
+    }
+
+    #[test]
+    fn test_estimate() {
+        let config = Config::default();
+        let calc = Calculator::new(&config);
+
+        let mut attendees = HashMap::new();
+        attendees.insert(Role::new("engineer"), 3);
+        attendees.insert(Role::new("director"), 1);
+
+        let estimate = calc.estimate(Local::now(), Duration::minutes(60), &attendees).unwrap();
+        assert_eq!(estimate.cost, Money::dollars(234));
+        assert_eq!(estimate.cost_per_minute, Money::from_f64(3.9));
+        assert_eq!(
+            estimate.by_role,
+            vec![
+                (Role::new("engineer"), 3, Money::dollars(129)),
+                (Role::new("director"), 1, Money::dollars(105)),
+            ]
+        );
+        assert_eq!(
+            estimate.variants,
+            vec![
+                (Variant::Minutes(25), Money::from_f64(97.5)),
+                (Variant::Minutes(50), Money::dollars(195)),
+                (Variant::OneFewer(Role::new("engineer")), Money::dollars(191)),
+                (Variant::OneFewer(Role::new("director")), Money::dollars(129)),
+            ]
+        );
+
+        // A 30-minute meeting is only compared against 25 minutes.
+        let estimate = calc.estimate(Local::now(), Duration::minutes(30), &attendees).unwrap();
+        assert_eq!(estimate.variants[0], (Variant::Minutes(25), Money::from_f64(97.5)));
+        assert_eq!(estimate.variants[1].0, Variant::OneFewer(Role::new("engineer")));
     }
 
     #[test]
@@ -105,10 +455,10 @@ mod tests {
         let calc = Calculator::new(&config);
 
         let mut attendees = HashMap::new();
-        attendees.insert(Role::Engineer, 2);
+        attendees.insert(Role::new("engineer"), 2);
 
-        // 2 engineers at $43/hr = $86/hr = $1.433.../min
-        let cost = calc.cost_per_minute(&attendees);
-        assert!((cost - 1.433333).abs() < 0.001);
+        // 2 engineers at $43/hr = $86/hr = $1.4333/min
+        let cost = calc.cost_per_minute(&attendees, &[]).unwrap();
+        assert_eq!(cost, Money::from_f64(1.4333));
     }
 }
@@ -1,4 +1,11 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::models::Rounding;
+use crate::money::{Currency, Locale, Money};
+use crate::reports::GroupBy;
+use crate::series::Recurrence;
+use crate::storage::StorageBackend;
 
 #[derive(Parser)]
 #[command(name = "meeting-meter")]
@@ -12,82 +19,87 @@ pub struct Cli {
 pub enum Commands {
     /// Start a live meeting timer
     Start {
-        #[arg(long, default_value = "0")]
-        engineers: u32,
-        #[arg(long, default_value = "0")]
-        senior_engineers: u32,
-        #[arg(long, default_value = "0")]
-        staff_engineers: u32,
-        #[arg(long, default_value = "0")]
-        principal_engineers: u32,
-        #[arg(long, default_value = "0")]
-        pms: u32,
-        #[arg(long, default_value = "0")]
-        senior_pms: u32,
-        #[arg(long, default_value = "0")]
-        director_pms: u32,
-        #[arg(long, default_value = "0")]
-        designers: u32,
-        #[arg(long, default_value = "0")]
-        senior_designers: u32,
-        #[arg(long, default_value = "0")]
-        analysts: u32,
-        #[arg(long, default_value = "0")]
-        senior_analysts: u32,
-        #[arg(long, default_value = "0")]
-        directors: u32,
-        #[arg(long, default_value = "0")]
-        vps: u32,
-        #[arg(long, default_value = "0")]
-        executives: u32,
-        #[arg(long, default_value = "0")]
-        generic: u32,
-    },
+        /// Attendees as role=count, repeatable (e.g., "-a engineer=3 -a pm=1")
+        #[arg(short = 'a', long = "attendee", value_name = "ROLE=COUNT")]
+        attendees: Vec<String>,
 
-    /// Log a past meeting
-    Log {
-        /// Start time (e.g., "2pm", "14:00", "2:30pm")
-        #[arg(long)]
-        start: Option<String>,
+        /// People from the directory attending, comma-separated (e.g., "priya,sam"); counted on top of --attendee
+        #[arg(long = "with", value_name = "NAMES", value_delimiter = ',')]
+        people: Vec<String>,
 
-        /// End time (e.g., "3pm", "15:00", "3:30pm")
+        /// Team that owns the meeting; attendees who aren't named people count towards it
         #[arg(long)]
-        end: Option<String>,
+        team: Option<String>,
 
-        /// Duration (e.g., "90m", "1h30m", "1.5h")
-        #[arg(long)]
+        /// Start from a saved template; --attendee then overrides individual role counts
+        #[arg(short = 't', long)]
+        template: Option<String>,
+
+        #[command(flatten)]
+        labels: LabelArgs,
+    },
+
+    /// Log a past meeting
+    Log(LogArgs),
+
+    /// Estimate what a meeting will cost before booking it (nothing is saved)
+    Estimate {
+        /// Planned length (e.g., "30m", "1h"); defaults to the template's
+        #[arg(short, long)]
         duration: Option<String>,
 
-        #[arg(long, default_value = "0")]
-        engineers: u32,
-        #[arg(long, default_value = "0")]
-        senior_engineers: u32,
-        #[arg(long, default_value = "0")]
-        staff_engineers: u32,
-        #[arg(long, default_value = "0")]
-        principal_engineers: u32,
-        #[arg(long, default_value = "0")]
-        pms: u32,
-        #[arg(long, default_value = "0")]
-        senior_pms: u32,
-        #[arg(long, default_value = "0")]
-        director_pms: u32,
-        #[arg(long, default_value = "0")]
-        designers: u32,
-        #[arg(long, default_value = "0")]
-        senior_designers: u32,
-        #[arg(long, default_value = "0")]
-        analysts: u32,
-        #[arg(long, default_value = "0")]
-        senior_analysts: u32,
-        #[arg(long, default_value = "0")]
-        directors: u32,
-        #[arg(long, default_value = "0")]
-        vps: u32,
-        #[arg(long, default_value = "0")]
-        executives: u32,
-        #[arg(long, default_value = "0")]
-        generic: u32,
+        /// Attendees as role=count, repeatable (e.g., "-a engineer=3 -a pm=1")
+        #[arg(short = 'a', long = "attendee", value_name = "ROLE=COUNT")]
+        attendees: Vec<String>,
+
+        /// Start from a saved template; --attendee then overrides individual role counts
+        #[arg(short = 't', long)]
+        template: Option<String>,
+    },
+
+    /// Show one meeting in full
+    Show {
+        /// Meeting id, or any unique prefix of it (as shown by `history`)
+        id: String,
+    },
+
+    /// Change a logged meeting's times, attendees, notes or labels
+    Edit(EditArgs),
+
+    /// Move a meeting to the trash
+    Delete {
+        /// Meeting id, or any unique prefix of it (as shown by `history`)
+        id: String,
+    },
+
+    /// List or restore deleted meetings
+    Trash {
+        #[command(subcommand)]
+        action: TrashCommands,
+    },
+
+    /// Manage reusable attendee mixes
+    Template {
+        #[command(subcommand)]
+        action: TemplateCommands,
+    },
+
+    /// Manage recurring meetings and project what they cost
+    Series {
+        #[command(subcommand)]
+        action: SeriesCommands,
+    },
+
+    /// Manage teams and the departments they roll up into
+    Team {
+        #[command(subcommand)]
+        action: TeamCommands,
+    },
+
+    /// Manage the people directory, so meetings can be attended by name
+    People {
+        #[command(subcommand)]
+        action: PeopleCommands,
     },
 
     /// Manage configuration
@@ -96,26 +108,347 @@ pub enum Commands {
         action: ConfigCommands,
     },
 
-    /// View meeting history
-    History {
-        /// Number of recent meetings to show
-        #[arg(short, long, default_value = "10")]
-        limit: usize,
+    /// Manage where meetings are stored
+    Storage {
+        #[command(subcommand)]
+        action: StorageCommands,
     },
 
+    /// Check the meetings file and recover it if it is damaged
+    Doctor,
+
+    /// View meeting history
+    History(HistoryArgs),
+
     /// Generate reports
     Report {
         /// Show report for current week
-        #[arg(long)]
+        #[arg(long, conflicts_with_all = ["month", "all"])]
         week: bool,
 
         /// Show report for current month
-        #[arg(long)]
+        #[arg(long, conflicts_with = "all")]
         month: bool,
 
         /// Show all-time report
         #[arg(long)]
         all: bool,
+
+        /// Number of most expensive meetings to list
+        #[arg(long, default_value = "5")]
+        top: usize,
+
+        /// Also break the totals down by this key
+        #[arg(long, value_enum)]
+        by: Option<GroupBy>,
+    },
+
+    /// Reprice saved meetings under the current rate table
+    Recalculate {
+        /// Only reprice meetings on or after this date (e.g., "2026-01-01", "-1w")
+        #[arg(long, allow_hyphen_values = true)]
+        since: Option<String>,
+
+        /// Only reprice meetings on or before this date (e.g., "2026-01-31", "yesterday")
+        #[arg(long, allow_hyphen_values = true)]
+        until: Option<String>,
+
+        /// Show what would change without saving anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Args)]
+pub struct LogArgs {
+    /// Start time (e.g., "2pm", "14:00", "2:30pm", "2026-10-14 14:30")
+    #[arg(long)]
+    pub start: Option<String>,
+
+    /// End time (e.g., "3pm", "15:00", "3:30pm", "2026-10-14 15:30")
+    #[arg(long)]
+    pub end: Option<String>,
+
+    /// Duration (e.g., "90m", "1h30m", "1.5h")
+    #[arg(long)]
+    pub duration: Option<String>,
+
+    /// Start and end in one go (e.g., "2pm-3:30pm", "14:00-15:00", "45 minutes ago")
+    #[arg(long, conflicts_with_all = ["start", "end", "duration"])]
+    pub when: Option<String>,
+
+    /// Day the meeting happened (e.g., "2026-10-14", "yesterday", "friday", "-2d")
+    #[arg(long, allow_hyphen_values = true)]
+    pub date: Option<String>,
+
+    /// Attendees as role=count, repeatable; add @from-to for part of the meeting (e.g., "-a engineer=3 -a pm=1@14:30-15:00")
+    #[arg(short = 'a', long = "attendee", value_name = "ROLE=COUNT")]
+    pub attendees: Vec<String>,
+
+    /// People from the directory attending, comma-separated, with @from-to if only there for part (e.g., "priya,sam@14:30")
+    #[arg(long = "with", value_name = "NAMES", value_delimiter = ',')]
+    pub people: Vec<String>,
+
+    /// Team that owns the meeting; attendees who aren't named people count towards it
+    #[arg(long)]
+    pub team: Option<String>,
+
+    /// Start from a saved template; --attendee then overrides individual role counts
+    #[arg(short = 't', long)]
+    pub template: Option<String>,
+
+    #[command(flatten)]
+    pub labels: LabelArgs,
+}
+
+#[derive(Args)]
+pub struct HistoryArgs {
+    /// Number of recent meetings to show
+    #[arg(short, long, default_value = "10")]
+    pub limit: usize,
+
+    /// Only show meetings on or after this date (e.g., "2026-01-01", "-1w")
+    #[arg(long, allow_hyphen_values = true)]
+    pub since: Option<String>,
+
+    /// Only show meetings on or before this date (e.g., "2026-01-31", "yesterday")
+    #[arg(long, allow_hyphen_values = true)]
+    pub until: Option<String>,
+
+    /// Only show meetings costing at least this much, in the display currency
+    #[arg(long)]
+    pub min_cost: Option<Money>,
+
+    /// Only show meetings attended by this role (e.g., "pm", "director")
+    #[arg(long)]
+    pub role: Option<String>,
+
+    /// Only show meetings whose title or notes contain this text
+    #[arg(long)]
+    pub search: Option<String>,
+
+    /// Only show meetings with this tag
+    #[arg(long)]
+    pub tag: Option<String>,
+
+    /// Only show meetings in this category
+    #[arg(long)]
+    pub category: Option<String>,
+}
+
+#[derive(Args)]
+pub struct EditArgs {
+    /// Meeting id, or any unique prefix of it (as shown by `history`)
+    pub id: String,
+
+    /// New start time; the meeting keeps its length unless --end or --duration is given
+    #[arg(long)]
+    pub start: Option<String>,
+
+    /// New end time
+    #[arg(long)]
+    pub end: Option<String>,
+
+    /// New duration (e.g., "90m", "1h30m")
+    #[arg(long)]
+    pub duration: Option<String>,
+
+    /// New start and end in one go (e.g., "2pm-3:30pm")
+    #[arg(long, conflicts_with_all = ["start", "end", "duration"])]
+    pub when: Option<String>,
+
+    /// Move the meeting to another day (e.g., "2026-10-14", "yesterday")
+    #[arg(long, allow_hyphen_values = true)]
+    pub date: Option<String>,
+
+    /// Replace the attendees, as role=count[@from-to], repeatable; named people are kept
+    #[arg(short = 'a', long = "attendee", value_name = "ROLE=COUNT")]
+    pub attendees: Vec<String>,
+
+    /// Replace the notes ("" clears them)
+    #[arg(long)]
+    pub notes: Option<String>,
+
+    /// Change the team that owns the meeting ("" clears it)
+    #[arg(long)]
+    pub team: Option<String>,
+
+    /// Replaces the title or category ("" clears them); --tag replaces all tags
+    #[command(flatten)]
+    pub labels: LabelArgs,
+}
+
+#[derive(Args)]
+pub struct LabelArgs {
+    /// Short description of the meeting (e.g., "Sprint 42 planning")
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Kind of meeting (e.g., standup, planning, 1:1, retro, all-hands, interview)
+    #[arg(long)]
+    pub category: Option<String>,
+
+    /// Free-form label, repeatable or comma-separated (e.g., "--tag q3-launch,infra")
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+}
+
+impl LabelArgs {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.category.is_none() && self.tags.is_empty()
+    }
+}
+
+#[derive(Subcommand)]
+pub enum TemplateCommands {
+    /// Save a named attendee mix, replacing any template with the same name
+    Add {
+        /// Template name (e.g., "standup")
+        name: String,
+
+        /// Attendees as role=count, repeatable (e.g., "-a engineer=5 -a senior_pm=1")
+        #[arg(short = 'a', long = "attendee", value_name = "ROLE=COUNT", required = true)]
+        attendees: Vec<String>,
+
+        /// Default duration for `log` (e.g., "15m", "1h")
+        #[arg(long)]
+        duration: Option<String>,
+
+        #[command(flatten)]
+        labels: LabelArgs,
+    },
+
+    /// List saved templates
+    List,
+
+    /// Delete a template
+    Remove {
+        /// Template name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TeamCommands {
+    /// Declare a team, replacing any team with the same name
+    Add {
+        /// Team name (e.g., "platform")
+        name: String,
+
+        /// Department the team rolls up into (e.g., "engineering")
+        #[arg(long)]
+        department: Option<String>,
+    },
+
+    /// List teams by department, with who is on each
+    List,
+
+    /// Remove a team (its people are left without one)
+    Remove {
+        /// Team name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PeopleCommands {
+    /// Add someone to the directory, replacing anyone with the same name
+    Add {
+        /// Name used with --with (e.g., "Priya")
+        name: String,
+
+        /// Role they are costed as (e.g., "engineer", "pm")
+        #[arg(long)]
+        role: String,
+
+        /// Hourly rate charged instead of the role's
+        #[arg(long)]
+        rate: Option<Money>,
+
+        /// Currency of --rate (e.g., "EUR"); defaults to the display currency
+        #[arg(long, requires = "rate")]
+        currency: Option<Currency>,
+
+        /// Team they belong to, declared with `team add` (e.g., "platform")
+        #[arg(long)]
+        team: Option<String>,
+    },
+
+    /// List everyone in the directory
+    List,
+
+    /// Remove someone from the directory (meetings they attended are kept)
+    Remove {
+        /// Name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SeriesCommands {
+    /// Declare a recurring meeting
+    Add(SeriesAddArgs),
+
+    /// List series with their projected cost
+    List,
+
+    /// Show a series' schedule and projected cost per week, quarter and year
+    Show {
+        /// Series name
+        name: String,
+    },
+
+    /// Delete a series (meetings already saved from it are kept)
+    Remove {
+        /// Series name
+        name: String,
+    },
+
+    /// Save every occurrence that has already happened to history
+    Materialize {
+        /// Only this series (defaults to all of them)
+        name: Option<String>,
+    },
+}
+
+#[derive(Args)]
+pub struct SeriesAddArgs {
+    /// Series name (e.g., "weekly-sync")
+    pub name: String,
+
+    /// How often the meeting happens
+    #[arg(long, value_enum)]
+    pub every: Recurrence,
+
+    /// Start of the first occurrence (e.g., "2026-10-05 10:00", "monday 10am")
+    #[arg(long)]
+    pub first: String,
+
+    /// Length of each occurrence (e.g., "30m", "1h")
+    #[arg(long)]
+    pub duration: String,
+
+    /// Attendees as role=count, repeatable (e.g., "-a engineer=4 -a director=1")
+    #[arg(short = 'a', long = "attendee", value_name = "ROLE=COUNT", required = true)]
+    pub attendees: Vec<String>,
+
+    /// Last day the series runs (e.g., "2026-12-31")
+    #[arg(long)]
+    pub until: Option<String>,
+
+    #[command(flatten)]
+    pub labels: LabelArgs,
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommands {
+    /// List deleted meetings
+    List,
+
+    /// Move a deleted meeting back into history
+    Restore {
+        /// Meeting id, or any unique prefix of it
+        id: String,
     },
 }
 
@@ -126,13 +459,177 @@ pub enum ConfigCommands {
         /// Role to set rate for
         role: String,
 
-        /// Hourly rate in USD
-        rate: f64,
+        /// Hourly rate
+        rate: Money,
+
+        /// Currency the rate is in (e.g., "EUR"); keeps the role's current one if omitted
+        #[arg(long)]
+        currency: Option<Currency>,
+
+        /// Day the rate takes effect (e.g., "2026-01-01"); defaults to today.
+        /// Meetings before it keep being priced at the earlier rate.
+        #[arg(long, allow_hyphen_values = true)]
+        from: Option<String>,
+    },
+
+    /// Set a role's rate from an annual salary, converted over the working year
+    SetSalary {
+        /// Role to set the salary for
+        role: String,
+
+        /// Annual salary (e.g., "150000" or "150,000")
+        salary: Money,
+
+        /// Currency the salary is in (e.g., "EUR"); keeps the role's current one if omitted
+        #[arg(long)]
+        currency: Option<Currency>,
+
+        /// Day the salary takes effect (e.g., "2026-01-01"); defaults to today
+        #[arg(long, allow_hyphen_values = true)]
+        from: Option<String>,
     },
 
     /// List all configured rates
     ListRates,
 
-    /// Reset all rates to defaults
+    /// Declare a new role
+    AddRole {
+        /// Role id used in attendee lists (e.g., "data_scientist")
+        id: String,
+
+        /// Hourly rate
+        #[arg(long)]
+        rate: Money,
+
+        /// Currency the rate is in (defaults to the display currency)
+        #[arg(long)]
+        currency: Option<Currency>,
+
+        /// Display name (defaults to the id in title case)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Alternative name accepted on the command line, repeatable
+        #[arg(long = "alias")]
+        aliases: Vec<String>,
+    },
+
+    /// Remove a role
+    RemoveRole {
+        /// Role to remove
+        role: String,
+    },
+
+    /// Change a cost setting
+    Set {
+        #[command(subcommand)]
+        setting: SetCommands,
+    },
+
+    /// Manage exchange rates between currencies
+    ExchangeRate {
+        #[command(subcommand)]
+        action: ExchangeRateCommands,
+    },
+
+    /// Reset roles, rates, overhead and context switch to defaults
     Reset,
 }
+
+#[derive(Subcommand)]
+pub enum SetCommands {
+    /// Benefits and overhead added on top of hourly rates, as a percentage
+    Overhead {
+        /// Percentage, e.g. 40 for 40%
+        #[arg(required_unless_present = "clear")]
+        percent: Option<f64>,
+
+        /// Only for this role, overriding the global percentage
+        #[arg(long)]
+        role: Option<String>,
+
+        /// Make the role use the global percentage again
+        #[arg(long, requires = "role", conflicts_with = "percent")]
+        clear: bool,
+    },
+
+    /// Focus time each attendee loses to a meeting, in minutes
+    ContextSwitch {
+        /// Minutes per attendee per meeting, e.g. 10
+        minutes: f64,
+    },
+
+    /// How meeting lengths are rounded before they are priced
+    Rounding {
+        #[arg(value_enum)]
+        policy: Rounding,
+    },
+
+    /// Contracted working hours in a year, before holidays and PTO
+    WorkingHours {
+        /// Hours per year, e.g. 2080 for 52 weeks of 40 hours
+        hours: f64,
+    },
+
+    /// Public holidays per year
+    Holidays {
+        /// Days per year, e.g. 10
+        days: u32,
+    },
+
+    /// Paid time off per year
+    Pto {
+        /// Days per year, e.g. 20
+        days: u32,
+    },
+
+    /// Currency costs are calculated and shown in
+    Currency {
+        /// Three-letter code, e.g. "EUR"
+        currency: Currency,
+    },
+
+    /// How amounts are written
+    Locale {
+        /// Language tag, e.g. "en-US", "en-GB" or "de-DE"
+        locale: Locale,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExchangeRateCommands {
+    /// Set what one unit of a currency is worth in USD
+    Set {
+        /// Three-letter code, e.g. "EUR"
+        currency: Currency,
+
+        /// Value of one unit in USD, e.g. 1.08
+        usd_value: f64,
+    },
+
+    /// Remove a currency's exchange rate
+    Remove {
+        currency: Currency,
+    },
+
+    /// Load exchange rates from a file with one "CODE VALUE" pair per line
+    Import {
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StorageCommands {
+    /// Show the active storage backend and how many meetings it holds
+    Status,
+
+    /// Copy every meeting, including the trash, from the JSON files into the SQLite database and switch to it
+    Migrate,
+
+    /// Switch the storage backend without copying any meetings
+    Use {
+        /// Backend to use
+        #[arg(value_enum)]
+        backend: StorageBackend,
+    },
+}
@@ -1,29 +1,166 @@
-use crate::config::ConfigManager;
-use crate::models::Role;
-use anyhow::{Result, anyhow};
+use crate::config::{self, ConfigManager};
+use crate::helpers::display_rate;
+use crate::models::{Config, Role, RoleDef, Rounding, WorkYear};
+use crate::money::{Currency, Locale, Money};
+use crate::storage::{self, MeetingFilter};
+use crate::time_parser;
+use anyhow::{Context, Result, anyhow};
+use chrono::{Local, NaiveDate};
+use std::fs;
+use std::path::Path;
+
+pub fn set_rate(
+    role_str: &str,
+    rate: Money,
+    currency: Option<Currency>,
+    from: Option<String>,
+) -> Result<()> {
+    let from = effective_date(from)?;
+
+    let config_mgr = ConfigManager::new()?;
+    let role = config_mgr.load()?.resolve_role(role_str)?;
+    config_mgr.set_rate(&role, rate, currency, from)?;
+
+    let config = config_mgr.load()?;
+    let def = config.role(&role).ok_or_else(|| anyhow!("Unknown role: {}", role))?;
+    let (rate, currency) = def.rate_on(from);
+    println!(
+        "✓ Set {} rate to {}/hr from {}",
+        def.name,
+        config.format_money(rate, currency),
+        from.format("%Y-%m-%d")
+    );
+    print_recalculate_hint(from);
+
+    Ok(())
+}
+
+pub fn set_salary(
+    role_str: &str,
+    salary: Money,
+    currency: Option<Currency>,
+    from: Option<String>,
+) -> Result<()> {
+    let from = effective_date(from)?;
 
-pub fn set_rate(role_str: &str, rate: f64) -> Result<()> {
-    let role = parse_role(role_str)?;
     let config_mgr = ConfigManager::new()?;
-    config_mgr.set_rate(role, rate)?;
+    let config = config_mgr.load()?;
+    let role = config.resolve_role(role_str)?;
+    let rate = config.work_year.hourly_rate(salary)?;
+    config_mgr.set_rate(&role, rate, currency, from)?;
 
-    println!("✓ Set {} rate to ${:.2}/hr", role, rate);
+    let config = config_mgr.load()?;
+    let def = config.role(&role).ok_or_else(|| anyhow!("Unknown role: {}", role))?;
+    let (rate, currency) = def.rate_on(from);
+    println!(
+        "✓ Set {} salary to {}/yr ({}/hr over {} working hours) from {}",
+        def.name,
+        config.format_money(salary, currency),
+        config.format_money(rate, currency),
+        config.work_year.working_hours(),
+        from.format("%Y-%m-%d")
+    );
+    print_recalculate_hint(from);
 
     Ok(())
 }
 
+/// The day a rate change takes effect: `--from`, or else today.
+fn effective_date(from: Option<String>) -> Result<NaiveDate> {
+    Ok(from
+        .map(|s| time_parser::parse_date(&s))
+        .transpose()?
+        .unwrap_or_else(|| Local::now().date_naive()))
+}
+
+fn print_recalculate_hint(from: NaiveDate) {
+    if from < Local::now().date_naive() {
+        println!("  Meetings already saved keep their cost. Run `meeting-meter recalculate` to reprice them.");
+    }
+}
+
 pub fn list_rates() -> Result<()> {
     let config_mgr = ConfigManager::new()?;
     let config = config_mgr.load()?;
 
-    println!("\nConfigured Hourly Rates:");
-    println!("{:-<50}", "");
+    println!("\nConfigured Rates:");
+    println!("{:-<66}", "");
 
-    let mut roles: Vec<_> = config.rates.iter().collect();
-    roles.sort_by_key(|(_, rate)| -(**rate as i64)); // Sort by rate descending
+    let today = Local::now().date_naive();
+    let mut roles = config
+        .roles
+        .iter()
+        .map(|def| Ok((def, config.get_rate(&def.id)?)))
+        .collect::<Result<Vec<_>>>()?;
+    // Sort by rate descending, compared in the display currency
+    roles.sort_by_key(|(_, converted)| std::cmp::Reverse(*converted));
 
-    for (role, rate) in roles {
-        println!("{:<30} ${:>8.2}/hr", format!("{}", role), rate);
+    for (def, converted_rate) in roles {
+        let overhead = def
+            .overhead_percent
+            .map(|percent| format!("  +{}% overhead", percent))
+            .unwrap_or_default();
+        let (rate, currency) = def.rate_on(today);
+        let converted = if currency == &config.currency {
+            String::new()
+        } else {
+            format!(
+                "  (≈ {}/hr)",
+                config.format_money(converted_rate, &config.currency)
+            )
+        };
+        let annual = config.format_money(config.work_year.annual_salary(rate), currency);
+        println!(
+            "{:<30} {:>14} {:>16}  ({}){}{}",
+            def.name,
+            display_rate(&def.id, &config),
+            format!("{}/yr", annual),
+            def.id,
+            overhead,
+            converted
+        );
+
+        if !def.rate_changes.is_empty() {
+            let changes: Vec<String> = def
+                .rate_changes
+                .iter()
+                .map(|change| {
+                    format!(
+                        "{}/hr from {}",
+                        config.format_money(change.rate, &change.currency),
+                        change.from.format("%Y-%m-%d")
+                    )
+                })
+                .collect();
+            println!(
+                "    History: {}/hr, then {}",
+                config.format_money(def.rate, &def.currency),
+                changes.join(", ")
+            );
+        }
+    }
+
+    println!("\nOverhead:       {}% on top of rates", config.overhead_percent);
+    println!(
+        "Context switch: {} minutes per attendee per meeting",
+        config.context_switch_minutes
+    );
+    println!("Rounding:       {}", config.rounding);
+    println!("Currency:       {} ({})", config.currency, config.locale);
+    let work_year = &config.work_year;
+    println!(
+        "Work year:      {} hours less {} holidays and {} PTO days = {} working hours",
+        work_year.hours,
+        work_year.holidays,
+        work_year.pto_days,
+        work_year.working_hours()
+    );
+
+    if !config.exchange_rates.is_empty() {
+        println!("\nExchange Rates (value in USD):");
+        for (currency, usd_value) in &config.exchange_rates {
+            println!("  1 {} = {} USD", currency, usd_value);
+        }
     }
 
     println!("\nConfig file: {}", config_mgr.config_path().display());
@@ -31,37 +168,271 @@ pub fn list_rates() -> Result<()> {
     Ok(())
 }
 
+pub fn add_role(
+    id: &str,
+    name: Option<String>,
+    rate: Money,
+    currency: Option<Currency>,
+    aliases: Vec<String>,
+) -> Result<()> {
+    let id = Role::normalize(id);
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(anyhow!(
+            "Invalid role id: '{}'. Use letters, digits and underscores, e.g. 'data_scientist'.",
+            id
+        ));
+    }
+
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+
+    let name = name.unwrap_or_else(|| title_case(&id));
+    let currency = currency.unwrap_or_else(|| config.currency.clone());
+    let role = RoleDef {
+        id: Role::new(&id),
+        name: name.clone(),
+        aliases,
+        rate,
+        currency: currency.clone(),
+        overhead_percent: None,
+        rate_changes: Vec::new(),
+    };
+    config_mgr.add_role(role)?;
+
+    println!(
+        "✓ Added {} role at {}/hr",
+        name,
+        config.format_money(rate, &currency)
+    );
+
+    Ok(())
+}
+
+pub fn remove_role(role_str: &str) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+    let role = config.resolve_role(role_str)?;
+    check_no_meetings_use(&config, &role)?;
+    let removed = config_mgr.remove_role(&role)?;
+
+    println!("✓ Removed {} role", removed.name);
+
+    Ok(())
+}
+
+/// Fails if a saved or trashed meeting includes `role`, since it could no
+/// longer be priced once the role is gone.
+fn check_no_meetings_use(config: &Config, role: &Role) -> Result<()> {
+    let store = storage::open(config)?;
+    let filter = MeetingFilter {
+        role: Some(role.clone()),
+        ..Default::default()
+    };
+    let count = store.find_meetings(&filter)?.len()
+        + store
+            .load_trash()?
+            .iter()
+            .filter(|trashed| filter.matches(&trashed.meeting))
+            .count();
+
+    if count > 0 {
+        return Err(anyhow!(
+            "The {} role is still used by {} saved meeting{}, including any in the trash. {} couldn't be repriced without it.",
+            config.role_name(role),
+            count,
+            if count == 1 { "" } else { "s" },
+            if count == 1 { "It" } else { "They" }
+        ));
+    }
+    Ok(())
+}
+
+pub fn set_overhead(percent: Option<f64>, role_str: Option<&str>) -> Result<()> {
+    if percent.is_some_and(|p| !(0.0..=1000.0).contains(&p)) {
+        return Err(anyhow!("Overhead must be a percentage between 0 and 1000."));
+    }
+
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+    let role = role_str.map(|r| config.resolve_role(r)).transpose()?;
+    config_mgr.set_overhead(role.as_ref(), percent)?;
+
+    match (role, percent) {
+        (Some(role), Some(percent)) => {
+            println!("✓ Set {} overhead to {}%", config.role_name(&role), percent)
+        }
+        (Some(role), None) => println!(
+            "✓ {} now uses the global overhead ({}%)",
+            config.role_name(&role),
+            config.overhead_percent
+        ),
+        (None, percent) => println!("✓ Set overhead to {}%", percent.unwrap_or(0.0)),
+    }
+
+    Ok(())
+}
+
+pub fn set_context_switch(minutes: f64) -> Result<()> {
+    if !(0.0..=480.0).contains(&minutes) {
+        return Err(anyhow!("Context switch time must be between 0 and 480 minutes."));
+    }
+
+    let config_mgr = ConfigManager::new()?;
+    config_mgr.set_context_switch(minutes)?;
+
+    println!("✓ Each attendee now loses {} minutes per meeting to context switching", minutes);
+
+    Ok(())
+}
+
+pub fn set_rounding(rounding: Rounding) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    config_mgr.set_rounding(rounding)?;
+
+    let description = match rounding {
+        Rounding::Exact => "billed to the second",
+        Rounding::NearestMinute => "rounded to the nearest minute",
+        Rounding::QuarterHour => "billed in 15-minute blocks",
+    };
+    println!("✓ Meetings are now {}", description);
+
+    Ok(())
+}
+
+pub fn set_working_hours(hours: f64) -> Result<()> {
+    if !(hours > 0.0 && hours <= 8784.0) {
+        return Err(anyhow!("Working hours must be between 0 and 8784 (every hour of a leap year)."));
+    }
+
+    let config_mgr = ConfigManager::new()?;
+    let mut work_year = config_mgr.load()?.work_year;
+    work_year.hours = hours;
+    config_mgr.set_work_year(work_year)?;
+
+    print_work_year(&work_year);
+
+    Ok(())
+}
+
+pub fn set_holidays(days: u32) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let mut work_year = config_mgr.load()?.work_year;
+    work_year.holidays = days;
+    config_mgr.set_work_year(work_year)?;
+
+    print_work_year(&work_year);
+
+    Ok(())
+}
+
+pub fn set_pto(days: u32) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let mut work_year = config_mgr.load()?.work_year;
+    work_year.pto_days = days;
+    config_mgr.set_work_year(work_year)?;
+
+    print_work_year(&work_year);
+
+    Ok(())
+}
+
+fn print_work_year(work_year: &WorkYear) {
+    println!(
+        "✓ Salaries now convert over {} working hours a year",
+        work_year.working_hours()
+    );
+    println!("  Existing rates are unchanged; re-enter salaries with `config set-salary` to convert them again.");
+}
+
+pub fn set_currency(currency: Currency) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    config_mgr.set_currency(currency.clone())?;
+
+    println!("✓ Costs are now calculated and shown in {}", currency);
+
+    Ok(())
+}
+
+pub fn set_locale(locale: Locale) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    config_mgr.set_locale(locale.clone())?;
+
+    let config = config_mgr.load()?;
+    println!(
+        "✓ Amounts are now written for {}, e.g. {}",
+        locale,
+        config.format_money(Money::from_f64(1234.5), &config.currency)
+    );
+
+    Ok(())
+}
+
+pub fn set_exchange_rate(currency: Currency, usd_value: f64) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    config_mgr.set_exchange_rates(&[(currency.clone(), usd_value)])?;
+
+    println!("✓ Set 1 {} = {} USD", currency, usd_value);
+
+    Ok(())
+}
+
+pub fn remove_exchange_rate(currency: &Currency) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    config_mgr.remove_exchange_rate(currency)?;
+
+    println!("✓ Removed the exchange rate for {}", currency);
+
+    Ok(())
+}
+
+pub fn import_exchange_rates(file: &Path) -> Result<()> {
+    let contents = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let rates = config::parse_exchange_rates(&contents)
+        .with_context(|| format!("Failed to parse {}", file.display()))?;
+    if rates.is_empty() {
+        return Err(anyhow!("No exchange rates found in {}", file.display()));
+    }
+
+    let config_mgr = ConfigManager::new()?;
+    config_mgr.set_exchange_rates(&rates)?;
+
+    println!(
+        "✓ Imported {} exchange rate{} from {}",
+        rates.len(),
+        if rates.len() == 1 { "" } else { "s" },
+        file.display()
+    );
+
+    Ok(())
+}
+
 pub fn reset() -> Result<()> {
     let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+    let defaults = Config::default();
+    for def in &config.roles {
+        if defaults.role(&def.id).is_none() {
+            check_no_meetings_use(&config, &def.id)?;
+        }
+    }
     config_mgr.reset()?;
 
-    println!("✓ Reset all rates to default values");
-
-    Ok(())
-}
-
-fn parse_role(s: &str) -> Result<Role> {
-    let normalized = s.to_lowercase().replace(" ", "_").replace("-", "_");
-
-    match normalized.as_str() {
-        "engineer" | "eng" => Ok(Role::Engineer),
-        "senior_engineer" | "senior_eng" | "sr_engineer" => Ok(Role::SeniorEngineer),
-        "staff_engineer" | "staff_eng" => Ok(Role::StaffEngineer),
-        "principal_engineer" | "principal_eng" | "principal" => Ok(Role::PrincipalEngineer),
-        "product_manager" | "pm" => Ok(Role::ProductManager),
-        "senior_pm" | "senior_product_manager" | "sr_pm" => Ok(Role::SeniorPm),
-        "director_pm" | "director_of_pm" | "pm_director" => Ok(Role::DirectorPm),
-        "designer" | "design" => Ok(Role::Designer),
-        "senior_designer" | "sr_designer" => Ok(Role::SeniorDesigner),
-        "analyst" => Ok(Role::Analyst),
-        "senior_analyst" | "sr_analyst" => Ok(Role::SeniorAnalyst),
-        "director" | "dir" => Ok(Role::Director),
-        "vp" | "vice_president" => Ok(Role::Vp),
-        "executive" | "exec" | "c_level" => Ok(Role::Executive),
-        "generic" => Ok(Role::Generic),
-        _ => Err(anyhow!(
-            "Unknown role: {}. Try one of: engineer, pm, designer, analyst, director, vp, executive",
-            s
-        )),
-    }
+    println!("✓ Reset roles, rates, overhead and context switch to default values");
+
+    Ok(())
+}
+
+fn title_case(id: &str) -> String {
+    id.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
 }
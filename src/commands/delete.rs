@@ -0,0 +1,28 @@
+use anyhow::Result;
+
+use crate::{
+    config::ConfigManager,
+    helpers::{display_meeting_cost, short_id},
+    storage,
+};
+
+pub fn run(id: &str) -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let storage = storage::open(&config)?;
+
+    let meeting = storage.find_by_id(id)?;
+    storage.trash_meeting(&meeting.id)?;
+
+    println!(
+        "✓ Moved meeting {} ({}, {}) to the trash",
+        short_id(&meeting.id),
+        meeting.start_time.format("%Y-%m-%d %-I:%M %p"),
+        display_meeting_cost(&meeting, &config)
+    );
+    println!(
+        "  Restore it with: meeting-meter trash restore {}",
+        short_id(&meeting.id)
+    );
+
+    Ok(())
+}
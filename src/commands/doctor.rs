@@ -0,0 +1,40 @@
+use anyhow::Result;
+
+use crate::{
+    config::ConfigManager,
+    storage::{self, RepairOutcome, StorageBackend},
+};
+
+pub fn run() -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+
+    match config.storage {
+        StorageBackend::Json => {
+            let store = storage::open_json()?;
+            match store.repair()? {
+                RepairOutcome::Healthy { meetings } => {
+                    println!("✓ Meetings file is healthy ({} meetings)", meetings);
+                }
+                RepairOutcome::Repaired { salvaged, backup } => {
+                    println!("⚠ Meetings file was damaged and has been rebuilt.");
+                    println!("  Recovered {} meetings.", salvaged);
+                    println!("  The original file was kept at {}", backup.display());
+                }
+            }
+        }
+        StorageBackend::Sqlite => {
+            let store = storage::open_sqlite()?;
+            let problems = store.integrity_check()?;
+            if problems.is_empty() {
+                println!("✓ Meetings database is healthy");
+            } else {
+                println!("⚠ SQLite reported problems with the meetings database:");
+                for problem in problems {
+                    println!("  {}", problem);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
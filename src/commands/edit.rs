@@ -0,0 +1,155 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local};
+
+use crate::{
+    calculator::Calculator,
+    cli::EditArgs,
+    config::ConfigManager,
+    helpers::{self, display_meeting_cost, print_meeting_details},
+    models::Meeting,
+    storage, time_parser,
+};
+
+pub fn run(args: EditArgs) -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let storage = storage::open(&config)?;
+
+    let mut meeting = storage.find_by_id(&args.id)?;
+    let previous = meeting.clone();
+
+    let changes_times = args.start.is_some()
+        || args.end.is_some()
+        || args.duration.is_some()
+        || args.date.is_some()
+        || args.when.is_some();
+    let changes_attendees = !args.attendees.is_empty();
+
+    if !changes_times
+        && !changes_attendees
+        && args.notes.is_none()
+        && args.team.is_none()
+        && args.labels.is_empty()
+    {
+        return Err(anyhow!(
+            "Nothing to change. Pass new times, --attendee, --notes, --team, --title, --category or --tag."
+        ));
+    }
+
+    if changes_times {
+        let (start_time, end_time) = edited_times(&meeting, &args)?;
+        if end_time <= start_time {
+            return Err(anyhow!(
+                "The meeting's end time ({}) must be after its start time ({}).",
+                end_time.format("%-I:%M %p"),
+                start_time.format("%-I:%M %p")
+            ));
+        }
+        // Attendance and pauses move with the meeting.
+        let shift = start_time - meeting.start_time;
+        for part in &mut meeting.attendance {
+            part.joined += shift;
+            part.left += shift;
+        }
+        for pause in &mut meeting.pauses {
+            pause.paused += shift;
+            pause.resumed += shift;
+        }
+        meeting.start_time = start_time;
+        meeting.end_time = end_time;
+    }
+
+    if changes_attendees {
+        let (specs, windows) = helpers::split_windows(&args.attendees);
+        let mut attendees = helpers::parse_attendees(&specs, &config)?;
+        helpers::add_people(&mut attendees, &meeting.people)?;
+        if attendees.is_empty() {
+            return Err(anyhow!("A meeting needs at least one attendee."));
+        }
+        meeting.attendees = attendees;
+        // The new attendees replace the old ones' times; named people keep theirs.
+        meeting.attendance.retain(|part| part.name.is_some());
+        helpers::add_attendance(&mut meeting, &windows, &[], &config)?;
+    } else if changes_times {
+        meeting.check_attendance().map_err(|err| {
+            anyhow!("{} Give the attendees again with --attendee to reset their times.", err)
+        })?;
+    }
+
+    if let Some(notes) = args.notes {
+        // An empty string clears the notes.
+        meeting.notes = Some(notes).filter(|n| !n.trim().is_empty());
+    }
+
+    if let Some(team) = args.team {
+        meeting.team = Some(team)
+            .filter(|t| !t.trim().is_empty())
+            .map(|t| config.resolve_team(&t))
+            .transpose()?;
+    }
+
+    helpers::apply_labels(&mut meeting, args.labels);
+
+    // Notes- and label-only edits keep the stored cost, so a later rate
+    // change doesn't silently reprice an old meeting.
+    if changes_times || changes_attendees {
+        Calculator::new(&config).update_meeting_cost(&mut meeting)?;
+    }
+
+    storage.update_meeting(&meeting)?;
+
+    println!("\n\u{2713} Meeting updated\n");
+    print_meeting_details(&meeting, &config);
+    if (meeting.cost, &meeting.currency) != (previous.cost, &previous.currency) {
+        println!("Previous Cost: {}", display_meeting_cost(&previous, &config));
+    }
+
+    Ok(())
+}
+
+/// Works out the meeting's new start and end. Whatever isn't given is kept:
+/// `--start` alone moves the meeting without changing its length, and
+/// `--date` alone moves it to the same time on another day.
+fn edited_times(meeting: &Meeting, args: &EditArgs) -> Result<(DateTime<Local>, DateTime<Local>)> {
+    let now = Local::now();
+    let date = match &args.date {
+        Some(date) => time_parser::parse_date(date)?,
+        None => meeting.start_time.date_naive(),
+    };
+    let parse_at = |s: &str| -> Result<DateTime<Local>> {
+        time_parser::to_local(time_parser::parse_datetime(s, date, now.naive_local())?)
+    };
+    let length = meeting.end_time - meeting.start_time;
+
+    if let Some(when) = &args.when {
+        let (start, end) = time_parser::parse_time_range(when, date, now.naive_local())?;
+        return Ok((time_parser::to_local(start)?, time_parser::to_local(end)?));
+    }
+
+    match (&args.start, &args.end, &args.duration) {
+        (Some(start), Some(end), None) => Ok((parse_at(start)?, parse_at(end)?)),
+        (Some(start), None, Some(duration)) => {
+            let start_dt = parse_at(start)?;
+            Ok((start_dt, time_parser::shift(start_dt, time_parser::parse_duration(duration)?)?))
+        }
+        (None, Some(end), Some(duration)) => {
+            let end_dt = parse_at(end)?;
+            Ok((time_parser::shift(end_dt, -time_parser::parse_duration(duration)?)?, end_dt))
+        }
+        (Some(start), None, None) => {
+            let start_dt = parse_at(start)?;
+            Ok((start_dt, start_dt + length))
+        }
+        (None, Some(end), None) => Ok((meeting.start_time, parse_at(end)?)),
+        (None, None, Some(duration)) => Ok((
+            meeting.start_time,
+            time_parser::shift(meeting.start_time, time_parser::parse_duration(duration)?)?,
+        )),
+        (None, None, None) => {
+            let start_dt = time_parser::to_local(date.and_time(meeting.start_time.time()))?;
+            Ok((start_dt, start_dt + length))
+        }
+        (Some(_), Some(_), Some(_)) => Err(anyhow!(
+            "Invalid arguments: Cannot provide --start, --end, and --duration simultaneously."
+        )),
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::{Result, anyhow};
+use chrono::{Duration, Local};
+
+use crate::{
+    calculator::{Calculator, Variant},
+    config::ConfigManager,
+    helpers::{self, display_cost, display_duration, summarize_attendees},
+    time_parser,
+};
+
+pub fn run(
+    duration: Option<String>,
+    attendees: Vec<String>,
+    template: Option<String>,
+) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let config = config_manager.load()?;
+
+    let template = template
+        .map(|name| config_manager.template(&name))
+        .transpose()?;
+    let attendees = helpers::resolve_attendees(template.as_ref(), &attendees, &config)?;
+    if attendees.is_empty() {
+        return Err(anyhow!(
+            "Please specify attendees with --attendee, e.g. '-a engineer=3', or a --template."
+        ));
+    }
+
+    let duration = match (duration, template.as_ref().and_then(|t| t.duration_minutes)) {
+        (Some(duration), _) => time_parser::parse_duration(&duration)?,
+        (None, Some(minutes)) => Duration::minutes(minutes),
+        (None, None) => {
+            return Err(anyhow!(
+                "Please give the planned length with --duration, e.g. '--duration 30m'."
+            ));
+        }
+    };
+    if duration <= Duration::zero() {
+        return Err(anyhow!("The duration must be longer than zero."));
+    }
+
+    let estimate = Calculator::new(&config).estimate(Local::now(), duration, &attendees)?;
+
+    println!("\nMeeting Estimate");
+    println!("{:═<63}", "");
+    println!();
+    println!(
+        "Attendees:       {}",
+        summarize_attendees(&attendees, &config)
+    );
+    println!("Duration:        {}", display_duration(&duration));
+    println!("Estimated Cost:  {}", display_cost(estimate.cost, &config));
+    println!(
+        "Cost per Minute: {}",
+        display_cost(estimate.cost_per_minute, &config)
+    );
+    println!();
+
+    let breakdown = &estimate.breakdown;
+    if !breakdown.overhead.is_zero() || !breakdown.context_switch.is_zero() {
+        println!("Breakdown:");
+        println!("  Rates:          {:>12}", display_cost(breakdown.base, &config));
+        println!("  Overhead:       {:>12}", display_cost(breakdown.overhead, &config));
+        println!(
+            "  Context switch: {:>12}",
+            display_cost(breakdown.context_switch, &config)
+        );
+        println!();
+    }
+
+    println!("By Role:");
+    for (role, count, cost) in &estimate.by_role {
+        println!(
+            "  {}x {:<18} @ {:<11}{:>12}",
+            count,
+            config.role_name(role),
+            helpers::display_rate(role, &config),
+            display_cost(*cost, &config)
+        );
+    }
+    println!();
+
+    println!("What If:");
+    for (variant, cost) in &estimate.variants {
+        let label = match variant {
+            Variant::Minutes(minutes) => format!("Only {} minutes", minutes),
+            Variant::OneFewer(role) => format!("One fewer {}", config.role_name(role)),
+        };
+        println!(
+            "  {:<34}{:>12}  (saves {})",
+            label,
+            display_cost(*cost, &config),
+            display_cost(estimate.cost - *cost, &config)
+        );
+    }
+
+    Ok(())
+}
@@ -1,10 +1,92 @@
 use anyhow::Result;
 
-pub fn run(limit: usize) -> Result<()> {
-    // TODO: Implement history viewing in Phase 4
+use crate::{
+    cli::HistoryArgs,
+    config::ConfigManager,
+    helpers::{
+        display_billed_duration, display_cost, display_labels, display_meeting_cost, display_people,
+        short_id, summarize_attendees,
+    },
+    models::Meeting,
+    money::Money,
+    storage::{self, MeetingFilter, MinCost},
+    time_parser,
+};
+
+pub fn run(args: HistoryArgs) -> Result<()> {
+    let HistoryArgs {
+        limit,
+        since,
+        until,
+        min_cost,
+        role,
+        search,
+        tag,
+        category,
+    } = args;
+
+    let (since, until) = time_parser::parse_date_bounds(since.as_deref(), until.as_deref())?;
+
+    let config = ConfigManager::new()?.load()?;
+
+    let filter = MeetingFilter {
+        since,
+        until,
+        min_cost: min_cost.map(|amount| MinCost::new(amount, &config)),
+        role: role.as_deref().map(|r| config.resolve_role(r)).transpose()?,
+        search,
+        tag: tag.as_deref().map(Meeting::normalize_label),
+        category: category.as_deref().map(Meeting::normalize_label),
+    };
+
+    let storage = storage::open(&config)?;
+    let meetings = storage.get_recent_meetings(&filter, limit)?;
+
+    if meetings.is_empty() {
+        println!("No meetings found.");
+        return Ok(());
+    }
+
+    println!("\nMeeting History (showing {})", meetings.len());
+    println!("{:═<63}", "");
+    println!();
+
+    for meeting in &meetings {
+        println!(
+            "{}  {}  {} - {}  ({})",
+            short_id(&meeting.id),
+            meeting.start_time.format("%Y-%m-%d"),
+            meeting.start_time.format("%-I:%M %p"),
+            meeting.end_time.format("%-I:%M %p"),
+            display_billed_duration(meeting)
+        );
+        let labels = display_labels(meeting);
+        if !labels.is_empty() {
+            println!("  {}", labels);
+        }
+        let attendees = summarize_attendees(&meeting.attendees, &config);
+        if meeting.people.is_empty() {
+            println!("  {}", attendees);
+        } else {
+            println!("  {} (incl. {})", attendees, display_people(&meeting.people));
+        }
+        if let Some(notes) = &meeting.notes {
+            println!("  Notes: {}", notes);
+        }
+        println!("  Cost: {}", display_meeting_cost(meeting, &config));
+        println!();
+    }
+
+    let total: Money = meetings
+        .iter()
+        .map(|m| config.to_display(m.cost, &m.currency))
+        .sum::<Result<Money>>()?;
+    println!("{:─<63}", "");
     println!(
-        "Meeting history (last {} meetings) - coming soon in Phase 4!",
-        limit
+        "Total: {} across {} meeting{}",
+        display_cost(total, &config),
+        meetings.len(),
+        if meetings.len() == 1 { "" } else { "s" }
     );
 
     Ok(())
@@ -1,64 +1,59 @@
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Duration, Local};
-use std::collections::HashMap;
 
 use crate::{
     calculator::Calculator,
+    cli::LogArgs,
     config::ConfigManager,
-    helpers::{self, display_attendees, display_cost, display_duration},
-    models::{Meeting, Role},
-    storage::Storage,
+    helpers::{self, print_meeting_details},
+    models::{Config, Meeting},
+    money::Money,
+    storage,
     time_parser,
 };
 
-#[allow(clippy::too_many_arguments)]
-pub fn run(
-    start: Option<String>,
-    end: Option<String>,
-    duration: Option<String>,
-    engineers: u32,
-    senior_engineers: u32,
-    staff_engineers: u32,
-    principal_engineers: u32,
-    pms: u32,
-    senior_pms: u32,
-    director_pms: u32,
-    designers: u32,
-    senior_designers: u32,
-    analysts: u32,
-    senior_analysts: u32,
-    directors: u32,
-    vps: u32,
-    executives: u32,
-    generic: u32,
-) -> Result<()> {
+pub fn run(args: LogArgs) -> Result<()> {
+    let LogArgs {
+        start,
+        end,
+        duration,
+        when,
+        date,
+        attendees,
+        people,
+        team,
+        template,
+        labels,
+    } = args;
+
     let config_manager = ConfigManager::new()?;
     let config = config_manager.load()?;
 
-    let attendees = helpers::parse_attendees(
-        engineers,
-        senior_engineers,
-        staff_engineers,
-        principal_engineers,
-        pms,
-        senior_pms,
-        director_pms,
-        designers,
-        senior_designers,
-        analysts,
-        senior_analysts,
-        directors,
-        vps,
-        executives,
-        generic,
-    );
+    let template = template
+        .map(|name| config_manager.template(&name))
+        .transpose()?;
+    let (attendees, attendee_windows) = helpers::split_windows(&attendees);
+    let (people, people_windows) = helpers::split_windows(&people);
+    let mut attendees = helpers::resolve_attendees(template.as_ref(), &attendees, &config)?;
+    let people = helpers::resolve_people(&people, &config)?;
+    helpers::add_people(&mut attendees, &people)?;
+    let team = team.map(|t| config.resolve_team(&t)).transpose()?;
 
     if attendees.is_empty() {
-        return Err(anyhow!("Cannot log a meeting with no attendees. Please specify attendees using the flags."));
+        return Err(anyhow!("Cannot log a meeting with no attendees. Please specify attendees with --attendee, e.g. '-a engineer=3', or --with."));
     }
 
+    // The template's length only fills in when the times given don't
+    // already fix it.
+    let duration = match (&template, &duration, &when, &start, &end) {
+        (Some(template), None, None, start, end) if start.is_none() || end.is_none() => {
+            template.duration_minutes.map(|minutes| format!("{}m", minutes))
+        }
+        _ => duration,
+    };
+
     let (start_time, end_time) =
-        calculate_start_end_times(start, end, duration)?;
+        calculate_start_end_times(start, end, duration, date, when)?;
 
     if end_time <= start_time {
         return Err(anyhow!(
@@ -68,14 +63,19 @@ pub fn run(
         ));
     }
 
-    let calculator = Calculator::new(&config);
-    let cost = calculator.calculate_cost(start_time, end_time, &attendees);
-
-    let meeting = Meeting::new(start_time, end_time, attendees.clone(), cost);
-    let storage = Storage::new()?;
+    let mut meeting = Meeting::new(start_time, end_time, attendees, Money::ZERO);
+    meeting.people = people;
+    meeting.team = team;
+    helpers::add_attendance(&mut meeting, &attendee_windows, &people_windows, &config)?;
+    Calculator::new(&config).update_meeting_cost(&mut meeting)?;
+    if let Some(template) = &template {
+        template.apply_labels(&mut meeting);
+    }
+    helpers::apply_labels(&mut meeting, labels);
+    let storage = storage::open(&config)?;
     storage.save_meeting(&meeting)?;
 
-    print_summary(&meeting, &config.rates)?;
+    print_summary(&meeting, &config)?;
 
     Ok(())
 }
@@ -84,46 +84,58 @@ fn calculate_start_end_times(
     start_str: Option<String>,
     end_str: Option<String>,
     duration_str: Option<String>,
+    date_str: Option<String>,
+    when_str: Option<String>,
 ) -> Result<(DateTime<Local>, DateTime<Local>)> {
     let now = Local::now();
     let today = now.date_naive();
+    let date = match date_str {
+        Some(date) => time_parser::parse_date(&date)?,
+        None => today,
+    };
+    let parse_at = |s: &str| -> Result<DateTime<Local>> {
+        time_parser::to_local(time_parser::parse_datetime(s, date, now.naive_local())?)
+    };
+
+    if let Some(when) = when_str {
+        let (start, end) = time_parser::parse_time_range(&when, date, now.naive_local())?;
+        return Ok((time_parser::to_local(start)?, time_parser::to_local(end)?));
+    }
 
     match (start_str, end_str, duration_str) {
         // Case 1: --start and --end provided
         (Some(start), Some(end), None) => {
-            let start_time = time_parser::parse_time(&start)?;
-            let end_time = time_parser::parse_time(&end)?;
-            let start_dt = today.and_time(start_time).and_local_timezone(Local).unwrap();
-            let end_dt = today.and_time(end_time).and_local_timezone(Local).unwrap();
+            let start_dt = parse_at(&start)?;
+            let end_dt = parse_at(&end)?;
             Ok((start_dt, end_dt))
         }
         // Case 2: --start and --duration provided
         (Some(start), None, Some(duration)) => {
-            let start_time = time_parser::parse_time(&start)?;
+            let start_dt = parse_at(&start)?;
             let duration_val = time_parser::parse_duration(&duration)?;
-            let start_dt = today.and_time(start_time).and_local_timezone(Local).unwrap();
-            let end_dt = start_dt + duration_val;
+            let end_dt = time_parser::shift(start_dt, duration_val)?;
             Ok((start_dt, end_dt))
         }
         // Case 3: --end and --duration provided (calculate start)
         (None, Some(end), Some(duration)) => {
-            let end_time = time_parser::parse_time(&end)?;
+            let end_dt = parse_at(&end)?;
             let duration_val = time_parser::parse_duration(&duration)?;
-            let end_dt = today.and_time(end_time).and_local_timezone(Local).unwrap();
-            let start_dt = end_dt - duration_val;
+            let start_dt = time_parser::shift(end_dt, -duration_val)?;
             Ok((start_dt, end_dt))
         }
         // Case 4: Only --duration provided (assume it just ended)
         (None, None, Some(duration)) => {
+            if date != today {
+                return Err(anyhow!("Invalid arguments: --date needs --start or --end to place the meeting on that day."));
+            }
             let duration_val = time_parser::parse_duration(&duration)?;
             let end_dt = now;
-            let start_dt = end_dt - duration_val;
+            let start_dt = time_parser::shift(end_dt, -duration_val)?;
             Ok((start_dt, end_dt))
         }
         // Case 5: --start provided, but no end or duration (assume 1 hour meeting)
         (Some(start), None, None) => {
-            let start_time = time_parser::parse_time(&start)?;
-            let start_dt = today.and_time(start_time).and_local_timezone(Local).unwrap();
+            let start_dt = parse_at(&start)?;
             let end_dt = start_dt + Duration::hours(1);
              println!("No end time or duration provided, assuming a 1-hour meeting.");
             Ok((start_dt, end_dt))
@@ -136,25 +148,14 @@ fn calculate_start_end_times(
             Err(anyhow!("Invalid arguments: Must provide --duration or --start along with --end."))
         }
         (None, None, None) => {
-            Err(anyhow!("Invalid arguments: Must provide time information, e.g., --duration '60m' or --start '2pm' --end '3pm'."))
+            Err(anyhow!("Invalid arguments: Must provide time information, e.g., --when '2pm-3pm', --duration '60m' or --start '2pm' --end '3pm'."))
         }
     }
 }
 
-
-fn print_summary(meeting: &Meeting, rates: &HashMap<Role, f64>) -> Result<()> {
-    let duration = meeting.end_time - meeting.start_time;
-
+fn print_summary(meeting: &Meeting, config: &Config) -> Result<()> {
     println!("\n\u{2713} Meeting logged successfully\n");
-    println!("Meeting Details:");
-    println!("  Start:    {}", meeting.start_time.format("%-I:%M %p"));
-    println!("  End:      {}", meeting.end_time.format("%-I:%M %p"));
-    println!("  Duration: {}", display_duration(&duration));
-    println!();
-    println!("Attendees:");
-    display_attendees(&meeting.attendees, rates);
-    println!();
-    println!("Total Cost: {}", display_cost(meeting.cost));
+    print_meeting_details(meeting, config);
 
     Ok(())
 }
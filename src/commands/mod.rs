@@ -1,5 +1,17 @@
 pub mod config;
+pub mod delete;
+pub mod doctor;
+pub mod edit;
+pub mod estimate;
 pub mod history;
 pub mod log;
+pub mod people;
+pub mod recalculate;
 pub mod report;
+pub mod series;
+pub mod show;
 pub mod start;
+pub mod storage;
+pub mod team;
+pub mod template;
+pub mod trash;
@@ -0,0 +1,87 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    config::ConfigManager,
+    helpers::display_person_rate,
+    models::Person,
+    money::{Currency, Money},
+};
+
+pub fn add(
+    name: &str,
+    role_str: &str,
+    rate: Option<Money>,
+    currency: Option<Currency>,
+    team: Option<String>,
+) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(',') {
+        return Err(anyhow!(
+            "Invalid name: '{}'. Names can't be empty or contain commas, since --with takes a comma-separated list.",
+            name
+        ));
+    }
+    if rate.is_some_and(|rate| rate < Money::ZERO) {
+        return Err(anyhow!("A rate can't be negative."));
+    }
+
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+
+    let person = Person {
+        name: name.to_string(),
+        role: config.resolve_role(role_str)?,
+        rate,
+        currency: currency.unwrap_or_else(|| config.currency.clone()),
+        team: team.map(|t| config.resolve_team(&t)).transpose()?,
+    };
+    let role_name = config.role_name(&person.role);
+    let rate = display_person_rate(&person, &config);
+
+    if config_mgr.save_person(person)? {
+        println!("✓ Updated {} ({}, {})", name, role_name, rate);
+    } else {
+        println!("✓ Added {} ({}, {})", name, role_name, rate);
+    }
+
+    Ok(())
+}
+
+pub fn list() -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+
+    if config.people.is_empty() {
+        println!("No people yet. Add someone with `meeting-meter people add <name> --role <role>`.");
+        return Ok(());
+    }
+
+    let mut people: Vec<&Person> = config.people.iter().collect();
+    people.sort_by(|a, b| a.team.cmp(&b.team).then_with(|| a.name.cmp(&b.name)));
+
+    println!("\nPeople:");
+    println!("{:-<66}", "");
+
+    for person in people {
+        let own_rate = if person.rate.is_some() { "  (own rate)" } else { "" };
+        println!(
+            "{:<20} {:<22} {:>14}  {}{}",
+            person.name,
+            config.role_name(&person.role),
+            display_person_rate(person, &config),
+            person.team.as_deref().unwrap_or("-"),
+            own_rate
+        );
+    }
+
+    Ok(())
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let removed = config_mgr.remove_person(name)?;
+
+    println!("✓ Removed {} from the people directory", removed.name);
+    println!("  Meetings they attended keep their name and cost.");
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use anyhow::Result;
+
+use crate::{
+    calculator::Calculator,
+    config::ConfigManager,
+    helpers::{display_cost, short_id},
+    models::{Config, Meeting},
+    money::Money,
+    storage::{self, MeetingFilter},
+    time_parser,
+};
+
+pub fn run(since: Option<String>, until: Option<String>, dry_run: bool) -> Result<()> {
+    let (since, until) = time_parser::parse_date_bounds(since.as_deref(), until.as_deref())?;
+
+    let config = ConfigManager::new()?.load()?;
+    let storage = storage::open(&config)?;
+    let filter = MeetingFilter {
+        since,
+        until,
+        ..Default::default()
+    };
+    let meetings = storage.find_meetings(&filter)?;
+
+    if meetings.is_empty() {
+        println!("No meetings found.");
+        return Ok(());
+    }
+
+    let calculator = Calculator::new(&config);
+    let mut changed = Vec::new();
+    let mut skipped = Vec::new();
+    let mut priced = 0;
+    let mut old_total = Money::ZERO;
+    let mut new_total = Money::ZERO;
+
+    for meeting in &meetings {
+        if let Some(name) = own_rate_attendee(meeting, &config) {
+            skipped.push((meeting, format!("{} is charged their own rate, which isn't dated", name)));
+            continue;
+        }
+        let mut repriced = meeting.clone();
+        if let Err(e) = calculator.update_meeting_cost(&mut repriced) {
+            skipped.push((meeting, e.to_string()));
+            continue;
+        }
+        // Compare in the display currency, which is what the reprice is in
+        let old_cost = config.to_display(meeting.cost, &meeting.currency)?;
+        priced += 1;
+        old_total += old_cost;
+        new_total += repriced.cost;
+
+        if repriced.cost != meeting.cost || repriced.currency != meeting.currency {
+            changed.push((old_cost, repriced));
+        }
+    }
+
+    if !skipped.is_empty() {
+        println!(
+            "\nSkipped {} meeting{} that can't be priced for their date:",
+            skipped.len(),
+            if skipped.len() == 1 { "" } else { "s" }
+        );
+        for (meeting, reason) in &skipped {
+            println!(
+                "  {}  {}  {}",
+                short_id(&meeting.id),
+                meeting.start_time.format("%Y-%m-%d"),
+                reason
+            );
+        }
+    }
+
+    if changed.is_empty() {
+        println!(
+            "✓ Nothing to reprice: {} meeting{} already at current rates",
+            priced,
+            if priced == 1 { "" } else { "s" }
+        );
+        return Ok(());
+    }
+
+    println!("\nRepriced Meetings");
+    println!("{:═<63}", "");
+    for (old_cost, meeting) in &changed {
+        println!(
+            "{}  {}  {:>12} → {:>12}  ({})",
+            short_id(&meeting.id),
+            meeting.start_time.format("%Y-%m-%d"),
+            display_cost(*old_cost, &config),
+            display_cost(meeting.cost, &config),
+            signed_cost(meeting.cost - *old_cost, &config)
+        );
+    }
+    println!("{:─<63}", "");
+    println!(
+        "Total: {} → {}  ({}) across {} meeting{}",
+        display_cost(old_total, &config),
+        display_cost(new_total, &config),
+        signed_cost(new_total - old_total, &config),
+        priced,
+        if priced == 1 { "" } else { "s" }
+    );
+
+    if dry_run {
+        println!("\nDry run: nothing was saved.");
+        return Ok(());
+    }
+
+    let repriced: Vec<Meeting> = changed.iter().map(|(_, meeting)| meeting.clone()).collect();
+    storage.update_meetings(&repriced)?;
+    println!(
+        "\n✓ Updated {} meeting{}",
+        changed.len(),
+        if changed.len() == 1 { "" } else { "s" }
+    );
+
+    Ok(())
+}
+
+/// A named attendee the people directory gives a rate of their own.
+/// Personal rates aren't effective-dated, so today's would be charged for
+/// any day.
+fn own_rate_attendee<'a>(meeting: &'a Meeting, config: &Config) -> Option<&'a str> {
+    meeting
+        .people
+        .iter()
+        .find(|participant| {
+            config
+                .person(&participant.name)
+                .is_some_and(|person| person.rate.is_some())
+        })
+        .map(|participant| participant.name.as_str())
+}
+
+fn signed_cost(amount: Money, config: &Config) -> String {
+    if amount > Money::ZERO {
+        format!("+{}", display_cost(amount, config))
+    } else {
+        display_cost(amount, config)
+    }
+}
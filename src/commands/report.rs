@@ -1,16 +1,202 @@
 use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
 
-pub fn run(week: bool, month: bool, all: bool) -> Result<()> {
-    // TODO: Implement reporting in Phase 4
-    if week {
-        println!("Weekly report - coming soon in Phase 4!");
+use crate::{
+    config::ConfigManager,
+    models::Config,
+    helpers::{display_cost, display_duration, summarize_attendees},
+    reports::{self, DepartmentTotals, GroupBy, GroupTotals, ReportSummary, WEEKDAYS},
+    storage::{self, MeetingFilter},
+};
+
+pub fn run(week: bool, month: bool, all: bool, top: usize, by: Option<GroupBy>) -> Result<()> {
+    let today = Local::now().date_naive();
+
+    let (title, range) = if week {
+        ("Weekly Report", Some(reports::week_range(today)?))
     } else if month {
-        println!("Monthly report - coming soon in Phase 4!");
+        ("Monthly Report", Some(reports::month_range(today)?))
     } else if all {
-        println!("All-time report - coming soon in Phase 4!");
+        ("All-Time Report", None)
     } else {
         println!("Please specify --week, --month, or --all");
+        return Ok(());
+    };
+
+    let filter = MeetingFilter {
+        since: range.map(|(start, _)| start),
+        until: range.map(|(_, end)| end),
+        ..Default::default()
+    };
+
+    let config = ConfigManager::new()?.load()?;
+    let meetings = storage::open(&config)?.find_meetings(&filter)?;
+    let meetings = reports::in_display_currency(meetings, &config)?;
+
+    match range {
+        Some((start, end)) => println!("\n{} ({})", title, display_range(start, end)),
+        None => println!("\n{}", title),
+    }
+    println!("{:═<63}", "");
+    println!();
+
+    if meetings.is_empty() {
+        println!("No meetings found.");
+        return Ok(());
+    }
+
+    let summary = reports::summarize(&meetings, &config, top)?;
+    print_summary(&summary, &config);
+
+    match by {
+        Some(GroupBy::Team) => {
+            println!();
+            print_teams(&reports::by_team(&meetings, &config)?, &config);
+        }
+        Some(by) => {
+            println!();
+            print_groups(by, &reports::group(&meetings, by), &config);
+        }
+        None => {}
     }
 
     Ok(())
 }
+
+fn display_range(start: DateTime<Local>, end: DateTime<Local>) -> String {
+    // `end` is exclusive, so show the last day actually covered.
+    let last_day = end - Duration::days(1);
+    format!(
+        "{} - {}",
+        start.format("%b %-d"),
+        last_day.format("%b %-d, %Y")
+    )
+}
+
+fn print_summary(summary: &ReportSummary, config: &Config) {
+    println!("Total Meetings: {}", summary.meeting_count);
+    println!(
+        "Total Time:     {}",
+        display_duration(&Duration::minutes(summary.total_minutes))
+    );
+    println!("Person-Hours:   {:.1}", summary.person_hours);
+    println!("Total Cost:     {}", display_cost(summary.total_cost, config));
+    println!();
+
+    println!("By Day:");
+    for (weekday, totals) in WEEKDAYS.iter().zip(summary.by_weekday.iter()) {
+        if totals.meetings == 0 {
+            continue;
+        }
+        println!(
+            "  {:<10}{:>3} meeting{:<4}{:<10}{:>12}",
+            weekday_name(*weekday),
+            totals.meetings,
+            if totals.meetings == 1 { "" } else { "s" },
+            display_duration(&Duration::minutes(totals.minutes)),
+            display_cost(totals.cost, config)
+        );
+    }
+    println!();
+
+    println!("By Role:");
+    for role in &summary.by_role {
+        println!(
+            "  {:<34}{:>8.1} person-hrs{:>12}",
+            format!("{} ({} attendees)", config.role_name(&role.role), role.attendees),
+            role.person_hours,
+            display_cost(role.cost, config)
+        );
+    }
+    println!();
+
+    if !summary.by_person.is_empty() {
+        println!("By Person:");
+        for person in &summary.by_person {
+            println!(
+                "  {:<22}{:>3} meeting{:<4}{:>8.1} hrs{:>12}",
+                person.name,
+                person.meetings,
+                if person.meetings == 1 { "" } else { "s" },
+                person.hours,
+                display_cost(person.cost, config)
+            );
+        }
+        println!();
+    }
+
+    if !summary.top_meetings.is_empty() {
+        println!("Most Expensive Meetings:");
+        for meeting in &summary.top_meetings {
+            println!(
+                "  {}  {:<10}{:>12}  {}",
+                meeting.start_time.format("%Y-%m-%d %-I:%M %p"),
+                display_duration(&meeting.billed_duration()),
+                display_cost(meeting.cost, config),
+                summarize_attendees(&meeting.attendees, config)
+            );
+        }
+        println!();
+    }
+
+    println!("Average Meeting Cost: {}", display_cost(summary.average_cost(), config));
+    println!(
+        "Average Meeting Duration: {}",
+        display_duration(&Duration::minutes(summary.average_minutes()))
+    );
+}
+
+fn print_groups(by: GroupBy, groups: &[GroupTotals], config: &Config) {
+    println!("{}:", by.heading());
+    for group in groups {
+        println!(
+            "  {:<22}{:>3} meeting{:<4}{:<10}{:>12}  (avg {})",
+            group.key.as_deref().unwrap_or("(none)"),
+            group.totals.meetings,
+            if group.totals.meetings == 1 { "" } else { "s" },
+            display_duration(&Duration::minutes(group.totals.minutes)),
+            display_cost(group.totals.cost, config),
+            display_cost(group.average_cost(), config)
+        );
+    }
+    if by == GroupBy::Tag {
+        println!("  Meetings with several tags count under each of them.");
+    }
+}
+
+fn print_teams(departments: &[DepartmentTotals], config: &Config) {
+    println!("{}:", GroupBy::Team.heading());
+    for department in departments {
+        println!(
+            "  {:<22}{:>3} meeting{:<4}{:<10}{:>12}",
+            department.department.as_deref().unwrap_or("(no department)"),
+            department.totals.meetings,
+            if department.totals.meetings == 1 { "" } else { "s" },
+            display_duration(&Duration::minutes(department.totals.minutes)),
+            display_cost(department.totals.cost, config)
+        );
+        for team in &department.teams {
+            println!(
+                "    {:<20}{:>3} meeting{:<4}{:<10}{:>12}",
+                team.key.as_deref().unwrap_or("(no team)"),
+                team.totals.meetings,
+                if team.totals.meetings == 1 { "" } else { "s" },
+                display_duration(&Duration::minutes(team.totals.minutes)),
+                display_cost(team.totals.cost, config)
+            );
+        }
+    }
+    println!("  Cross-team meetings are split by what each team's attendees cost.");
+}
+
+fn weekday_name(weekday: chrono::Weekday) -> &'static str {
+    match weekday {
+        chrono::Weekday::Mon => "Monday",
+        chrono::Weekday::Tue => "Tuesday",
+        chrono::Weekday::Wed => "Wednesday",
+        chrono::Weekday::Thu => "Thursday",
+        chrono::Weekday::Fri => "Friday",
+        chrono::Weekday::Sat => "Saturday",
+        chrono::Weekday::Sun => "Sunday",
+    }
+}
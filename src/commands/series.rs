@@ -0,0 +1,327 @@
+use anyhow::{Result, anyhow};
+use chrono::{Duration, Local, NaiveDateTime};
+
+use crate::{
+    calculator::Calculator,
+    cli::SeriesAddArgs,
+    config::ConfigManager,
+    helpers::{self, display_cost, display_duration, summarize_attendees},
+    models::Meeting,
+    money::Money,
+    series::{self, Series},
+    storage::{self, MeetingStore},
+    time_parser,
+};
+
+pub fn add(args: SeriesAddArgs) -> Result<()> {
+    let name = Meeting::normalize_label(&args.name);
+    if name.is_empty() {
+        return Err(anyhow!(
+            "Please give the series a name, e.g. 'weekly-sync'."
+        ));
+    }
+
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+    if config_mgr.load_series()?.iter().any(|s| s.name == name) {
+        return Err(already_exists(&name));
+    }
+
+    let attendees = helpers::parse_attendees(&args.attendees, &config)?;
+    if attendees.is_empty() {
+        return Err(anyhow!(
+            "A series needs at least one attendee, e.g. '-a engineer=4'."
+        ));
+    }
+
+    let now = Local::now().naive_local();
+    let first = time_parser::parse_datetime(&args.first, now.date(), now)?;
+    let duration = time_parser::parse_duration(&args.duration)?;
+    if duration <= Duration::zero() {
+        return Err(anyhow!("The duration must be longer than zero."));
+    }
+    let until = args
+        .until
+        .map(|d| time_parser::parse_date(&d))
+        .transpose()?;
+    if until.is_some_and(|until| until < first.date()) {
+        return Err(anyhow!("--until must not be before the first occurrence."));
+    }
+
+    let labels = args.labels;
+    let series = Series {
+        name: name.clone(),
+        every: args.every,
+        first,
+        duration_minutes: duration.num_minutes(),
+        until,
+        title: labels
+            .title
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty()),
+        category: labels
+            .category
+            .map(|c| Meeting::normalize_label(&c))
+            .filter(|c| !c.is_empty()),
+        tags: Meeting::parse_tags(&labels.tags),
+        materialized_through: None,
+        attendees: attendees.into_iter().collect(),
+    };
+
+    let projection = series::project(
+        &series,
+        &Calculator::new(&config),
+        pricing_time(&series, now),
+    )?;
+    config_mgr.update_series(|all| {
+        // Checked again in case another terminal added it meanwhile.
+        if all.iter().any(|s| s.name == name) {
+            return Err(already_exists(&name));
+        }
+        all.push(series);
+        Ok(())
+    })?;
+
+    println!(
+        "✓ Added {} series {}: {} per occurrence, {} per year",
+        args.every,
+        name,
+        display_cost(projection.per_occurrence, &config),
+        display_cost(projection.per_year, &config)
+    );
+
+    Ok(())
+}
+
+pub fn list() -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+    let all = config_mgr.load_series()?;
+
+    if all.is_empty() {
+        println!("No recurring series yet. Add one with `meeting-meter series add`.");
+        return Ok(());
+    }
+
+    let calculator = Calculator::new(&config);
+    let now = Local::now().naive_local();
+
+    println!("\nRecurring Series:");
+    println!("{:-<75}", "");
+
+    let mut total_per_year = Money::ZERO;
+    for series in &all {
+        let projection = series::project(series, &calculator, pricing_time(series, now))?;
+        total_per_year += projection.per_year;
+        let next = series
+            .next_occurrence(now)
+            .map(|next| next.format("%a %Y-%m-%d %-I:%M %p").to_string())
+            .unwrap_or_else(|| "ended".to_string());
+
+        println!(
+            "{:<20}{:<10}{:<26}{:>12}/yr",
+            series.name,
+            series.every.to_string(),
+            next,
+            display_cost(projection.per_year, &config)
+        );
+    }
+
+    println!("{:-<75}", "");
+    println!("Projected total: {} per year", display_cost(total_per_year, &config));
+    println!("\nSeries file: {}", config_mgr.series_path().display());
+
+    Ok(())
+}
+
+pub fn show(name: &str) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+    let all = config_mgr.load_series()?;
+    let series = find(&all, name)?;
+
+    let now = Local::now().naive_local();
+    let projection = series::project(series, &Calculator::new(&config), pricing_time(series, now))?;
+
+    println!("\nSeries: {}", series.name);
+    if let Some(title) = &series.title {
+        println!("  Title:     {}", title);
+    }
+    println!(
+        "  Schedule:  {} from {}{}",
+        series.every,
+        series.first.format("%a %Y-%m-%d %-I:%M %p"),
+        series
+            .until
+            .map(|until| format!(" until {}", until.format("%Y-%m-%d")))
+            .unwrap_or_default()
+    );
+    println!("  Duration:  {}", display_duration(&series.duration()));
+    println!(
+        "  Attendees: {}",
+        summarize_attendees(&series.attendee_map(), &config)
+    );
+    if let Some(category) = &series.category {
+        println!("  Category:  {}", category);
+    }
+    if !series.tags.is_empty() {
+        println!("  Tags:      {}", series.tags.join(", "));
+    }
+    match series.next_occurrence(now) {
+        Some(next) => println!("  Next:      {}", next.format("%a %Y-%m-%d %-I:%M %p")),
+        None => println!("  Next:      (series has ended)"),
+    }
+    if let Some(through) = series.materialized_through {
+        println!("  Saved to history through {}", through.format("%Y-%m-%d"));
+    }
+    println!();
+
+    println!("Projected Cost (at current rates):");
+    println!(
+        "  Per occurrence: {:>12}",
+        display_cost(projection.per_occurrence, &config)
+    );
+    println!(
+        "  Per week:       {:>12}",
+        display_cost(projection.per_week, &config)
+    );
+    println!(
+        "  Per quarter:    {:>12}",
+        display_cost(projection.per_quarter, &config)
+    );
+    println!(
+        "  Per year:       {:>12}",
+        display_cost(projection.per_year, &config)
+    );
+    println!(
+        "  Person-hours per year: {:.1}",
+        projection.person_hours_per_year
+    );
+
+    Ok(())
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let removed = config_mgr.update_series(|all| {
+        let removed = find(all, name)?.name.clone();
+        all.retain(|s| s.name != removed);
+        Ok(removed)
+    })?;
+
+    println!("✓ Removed series {}", removed);
+
+    Ok(())
+}
+
+/// Saves every occurrence that has finished since the series was last
+/// materialized, so running this repeatedly never saves one twice.
+pub fn materialize(name: Option<&str>) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+    let mut all = config_mgr.load_series()?;
+    if let Some(name) = name {
+        find(&all, name)?;
+    }
+
+    let storage = storage::open(&config)?;
+    let calculator = Calculator::new(&config);
+    let now = Local::now().naive_local();
+    let mut saved_any = false;
+
+    for series in all.iter_mut() {
+        if name.is_some_and(|name| Meeting::normalize_label(name) != series.name) {
+            continue;
+        }
+
+        let (count, cost) = materialize_series(series, storage.as_ref(), &calculator, now)?;
+        if count > 0 {
+            saved_any = true;
+            println!(
+                "✓ Saved {} occurrence{} of {} ({})",
+                count,
+                if count == 1 { "" } else { "s" },
+                series.name,
+                display_cost(cost, &config)
+            );
+        }
+    }
+
+    if saved_any {
+        // The meetings are saved first, outside the config lock, which may
+        // be the same lock as the data directory's. Only the progress marks
+        // are written back, so edits made meanwhile aren't lost.
+        config_mgr.update_series(|saved| {
+            for series in saved.iter_mut() {
+                if let Some(done) = all.iter().find(|s| s.name == series.name) {
+                    series.materialized_through =
+                        series.materialized_through.max(done.materialized_through);
+                }
+            }
+            Ok(())
+        })?;
+    } else {
+        println!("Nothing to save; every past occurrence is already in history.");
+    }
+
+    Ok(())
+}
+
+fn materialize_series(
+    series: &mut Series,
+    storage: &dyn MeetingStore,
+    calculator: &Calculator,
+    now: NaiveDateTime,
+) -> Result<(usize, Money)> {
+    let from = match series.materialized_through {
+        Some(through) => through + Duration::seconds(1),
+        None => series.first,
+    };
+
+    let mut count = 0;
+    let mut cost = Money::ZERO;
+    for start in series.occurrences(from, now) {
+        if start + series.duration() > now {
+            break;
+        }
+        let meeting = series.meeting_at(start, calculator)?;
+        storage.save_meeting(&meeting)?;
+        series.materialized_through = Some(start);
+        count += 1;
+        cost += meeting.cost;
+    }
+
+    Ok((count, cost))
+}
+
+fn already_exists(name: &str) -> anyhow::Error {
+    anyhow!(
+        "A series named '{}' already exists. Remove it first to redefine it.",
+        name
+    )
+}
+
+fn find<'a>(all: &'a [Series], name: &str) -> Result<&'a Series> {
+    let name = Meeting::normalize_label(name);
+    all.iter().find(|s| s.name == name).ok_or_else(|| {
+        let known: Vec<&str> = all.iter().map(|s| s.name.as_str()).collect();
+        if known.is_empty() {
+            anyhow!(
+                "Unknown series: {}. Create one with `meeting-meter series add`.",
+                name
+            )
+        } else {
+            anyhow!(
+                "Unknown series: {}. Known series: {}",
+                name,
+                known.join(", ")
+            )
+        }
+    })
+}
+
+/// Occurrence used to price a series: the next one, or the first if it has
+/// already ended.
+fn pricing_time(series: &Series, now: NaiveDateTime) -> NaiveDateTime {
+    series.next_occurrence(now).unwrap_or(series.first)
+}
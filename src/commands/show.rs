@@ -0,0 +1,15 @@
+use anyhow::Result;
+
+use crate::{config::ConfigManager, helpers::print_meeting_details, storage};
+
+pub fn run(id: &str) -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let storage = storage::open(&config)?;
+
+    let meeting = storage.find_by_id(id)?;
+
+    println!();
+    print_meeting_details(&meeting, &config);
+
+    Ok(())
+}
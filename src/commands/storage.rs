@@ -0,0 +1,58 @@
+use anyhow::Result;
+
+use crate::{
+    config::ConfigManager,
+    storage::{self, MeetingStore, StorageBackend},
+};
+
+pub fn status() -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let store = storage::open(&config)?;
+    let count = store.load_all_meetings()?.len();
+
+    println!("Storage backend: {}", config.storage);
+    println!("Location:        {}", store.location().display());
+    println!("Meetings:        {}", count);
+
+    Ok(())
+}
+
+pub fn migrate() -> Result<()> {
+    let json = storage::open_json()?;
+    let (meetings, trash) = json.export()?;
+
+    let sqlite = storage::open_sqlite()?;
+    let added = sqlite.import_meetings(&meetings)?;
+    let trashed = sqlite.import_trash(&trash)?;
+
+    ConfigManager::new()?.set_storage(StorageBackend::Sqlite)?;
+
+    println!(
+        "✓ Copied {} of {} meetings from {} into {}",
+        added,
+        meetings.len(),
+        json.location().display(),
+        sqlite.location().display()
+    );
+    if added < meetings.len() {
+        println!(
+            "  {} were already in the database and were skipped.",
+            meetings.len() - added
+        );
+    }
+    if !trash.is_empty() {
+        println!("✓ Copied {} of {} meetings in the trash", trashed, trash.len());
+    }
+    println!("✓ Now using the sqlite backend.");
+    println!("  The JSON files were read but not changed; `storage use json` switches back to them.");
+
+    Ok(())
+}
+
+pub fn use_backend(backend: StorageBackend) -> Result<()> {
+    ConfigManager::new()?.set_storage(backend)?;
+
+    println!("✓ Now using the {} backend", backend);
+
+    Ok(())
+}
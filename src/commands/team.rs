@@ -0,0 +1,85 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    config::ConfigManager,
+    models::{Meeting, Team},
+};
+
+pub fn add(name: &str, department: Option<String>) -> Result<()> {
+    let name = Meeting::normalize_label(name);
+    if name.is_empty() {
+        return Err(anyhow!("Please give the team a name, e.g. 'platform'."));
+    }
+
+    let team = Team {
+        name: name.clone(),
+        department: department
+            .map(|d| Meeting::normalize_label(&d))
+            .filter(|d| !d.is_empty()),
+    };
+    let in_department = team
+        .department
+        .as_ref()
+        .map(|d| format!(" in {}", d))
+        .unwrap_or_default();
+
+    if ConfigManager::new()?.save_team(team)? {
+        println!("✓ Updated team {}{}", name, in_department);
+    } else {
+        println!("✓ Added team {}{}", name, in_department);
+    }
+
+    Ok(())
+}
+
+pub fn list() -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+
+    if config.teams.is_empty() {
+        println!("No teams yet. Add one with `meeting-meter team add <name> --department <department>`.");
+        return Ok(());
+    }
+
+    let mut teams: Vec<&Team> = config.teams.iter().collect();
+    // Teams without a department go last.
+    teams.sort_by(|a, b| {
+        (a.department.is_none(), &a.department, &a.name).cmp(&(
+            b.department.is_none(),
+            &b.department,
+            &b.name,
+        ))
+    });
+
+    println!("\nTeams:");
+    println!("{:-<50}", "");
+
+    let mut department = None;
+    for (i, team) in teams.iter().enumerate() {
+        if i == 0 || team.department != department {
+            department = team.department.clone();
+            println!("{}", department.as_deref().unwrap_or("(no department)"));
+        }
+        let members: Vec<&str> = config
+            .people
+            .iter()
+            .filter(|p| p.team.as_deref() == Some(team.name.as_str()))
+            .map(|p| p.name.as_str())
+            .collect();
+        if members.is_empty() {
+            println!("  {}", team.name);
+        } else {
+            println!("  {:<20} {}", team.name, members.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    let removed = ConfigManager::new()?.remove_team(name)?;
+
+    println!("✓ Removed team {}", removed.name);
+    println!("  Its people are left without a team; meetings it owned are kept.");
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    cli::LabelArgs,
+    config::ConfigManager,
+    helpers::{self, summarize_attendees},
+    models::{Meeting, Template},
+    time_parser,
+};
+
+pub fn add(
+    name: &str,
+    attendees: Vec<String>,
+    duration: Option<String>,
+    labels: LabelArgs,
+) -> Result<()> {
+    let name = Meeting::normalize_label(name);
+    if name.is_empty() {
+        return Err(anyhow!("Please give the template a name, e.g. 'standup'."));
+    }
+
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+
+    let attendees = helpers::parse_attendees(&attendees, &config)?;
+    if attendees.is_empty() {
+        return Err(anyhow!(
+            "A template needs at least one attendee, e.g. '-a engineer=5'."
+        ));
+    }
+
+    let duration_minutes = duration
+        .map(|d| time_parser::parse_duration(&d).map(|d| d.num_minutes()))
+        .transpose()?;
+
+    let template = Template {
+        name: name.clone(),
+        duration_minutes,
+        title: labels
+            .title
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty()),
+        category: labels
+            .category
+            .map(|c| Meeting::normalize_label(&c))
+            .filter(|c| !c.is_empty()),
+        tags: Meeting::parse_tags(&labels.tags),
+        attendees: attendees.into_iter().collect(),
+    };
+
+    if config_mgr.save_template(template)? {
+        println!("✓ Updated template {}", name);
+    } else {
+        println!("✓ Added template {}", name);
+    }
+
+    Ok(())
+}
+
+pub fn list() -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let config = config_mgr.load()?;
+    let templates = config_mgr.load_templates()?;
+
+    if templates.is_empty() {
+        println!("No templates yet. Add one with `meeting-meter template add`.");
+        return Ok(());
+    }
+
+    println!("\nTemplates:");
+    println!("{:-<50}", "");
+
+    for template in &templates {
+        let attendees = template.attendees.clone().into_iter().collect();
+        println!("{}", template.name);
+        println!("  {}", summarize_attendees(&attendees, &config));
+
+        let mut details = Vec::new();
+        if let Some(minutes) = template.duration_minutes {
+            details.push(format!("{}m", minutes));
+        }
+        if let Some(title) = &template.title {
+            details.push(format!("\"{}\"", title));
+        }
+        if let Some(category) = &template.category {
+            details.push(format!("[{}]", category));
+        }
+        details.extend(template.tags.iter().map(|tag| format!("#{}", tag)));
+        if !details.is_empty() {
+            println!("  {}", details.join(" "));
+        }
+    }
+
+    println!(
+        "\nTemplates file: {}",
+        config_mgr.templates_path().display()
+    );
+
+    Ok(())
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    let config_mgr = ConfigManager::new()?;
+    let removed = config_mgr.remove_template(name)?;
+
+    println!("✓ Removed template {}", removed.name);
+
+    Ok(())
+}
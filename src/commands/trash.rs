@@ -0,0 +1,64 @@
+use anyhow::Result;
+
+use crate::{
+    config::ConfigManager,
+    helpers::{display_meeting_cost, short_id, summarize_attendees},
+    storage,
+};
+
+pub fn list() -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let storage = storage::open(&config)?;
+
+    let mut trash = storage.load_trash()?;
+    if trash.is_empty() {
+        println!("The trash is empty.");
+        return Ok(());
+    }
+    trash.sort_by_key(|t| std::cmp::Reverse(t.deleted_at));
+
+    println!(
+        "\nTrash ({} meeting{})",
+        trash.len(),
+        if trash.len() == 1 { "" } else { "s" }
+    );
+    println!("{:═<63}", "");
+    println!();
+
+    for trashed in &trash {
+        let meeting = &trashed.meeting;
+        println!(
+            "{}  {} - {}  {}",
+            short_id(&meeting.id),
+            meeting.start_time.format("%Y-%m-%d %-I:%M %p"),
+            meeting.end_time.format("%-I:%M %p"),
+            display_meeting_cost(meeting, &config)
+        );
+        println!("  {}", summarize_attendees(&meeting.attendees, &config));
+        println!(
+            "  Deleted: {}",
+            trashed.deleted_at.format("%Y-%m-%d %-I:%M %p")
+        );
+        println!();
+    }
+
+    Ok(())
+}
+
+pub fn restore(id: &str) -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let storage = storage::open(&config)?;
+
+    let trash = storage.load_trash()?;
+    let id = storage::resolve_id(trash.iter().map(|t| t.meeting.id.as_str()), id)?;
+    let meeting = storage.restore_meeting(&id)?;
+
+    println!(
+        "✓ Restored meeting {} ({}, {})",
+        short_id(&meeting.id),
+        meeting.start_time.format("%Y-%m-%d %-I:%M %p"),
+        display_meeting_cost(&meeting, &config)
+    );
+
+    Ok(())
+}
@@ -1,9 +1,41 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
+use chrono::NaiveDate;
 use directories::ProjectDirs;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::models::{Config, Role};
+use crate::models::{
+    Config, Meeting, Person, Role, RoleDef, Rounding, Team, Template, WorkYear, default_roles,
+};
+use crate::money::{Currency, Locale, Money};
+use crate::series::Series;
+use crate::storage::{DirLock, StorageBackend, backup_copy, write_atomic};
+
+/// Current layout of `config.toml`.
+///
+/// 1. Unversioned, with hourly rates in a `[rates]` table keyed by role (up to 0.4).
+/// 2. `schema_version` field, roles declared under `[[roles]]`.
+/// 3. `overhead_percent` and `context_switch_minutes` replace the
+///    `include_benefits` flag and `context_switch_multiplier`.
+/// 4. Adds `rounding`, `currency`, `locale`, `[work_year]`,
+///    `[exchange_rates]`, `[[teams]]` and `[[people]]`, and roles gain a
+///    `currency` and `rate_changes`. Nothing is rewritten; the bump keeps
+///    older versions, which would drop these on their next save, from
+///    opening the file.
+pub const CONFIG_SCHEMA_VERSION: u32 = 4;
+
+/// Current layout of `templates.toml`.
+pub const TEMPLATES_SCHEMA_VERSION: u32 = 1;
+
+/// Current layout of `series.toml`.
+pub const SERIES_SCHEMA_VERSION: u32 = 1;
+
+/// Upgrades from version `n` to `n + 1` live at index `n - 1`.
+const MIGRATIONS: [fn(&mut toml::Table) -> Result<()>; 3] =
+    [migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4];
 
 pub struct ConfigManager {
     config_path: PathBuf,
@@ -23,43 +55,843 @@ impl ConfigManager {
     }
 
     pub fn load(&self) -> Result<Config> {
-        if !self.config_path.exists() {
-            // Create default config
-            let config = Config::default();
-            self.save(&config)?;
+        let (config, from_version) = self.read()?;
+        if from_version.is_some_and(|version| version >= CONFIG_SCHEMA_VERSION) {
             return Ok(config);
         }
 
+        // Create or upgrade the file. Another process may have done so while
+        // we waited for the lock.
+        self.update(|config| Ok(config.clone()))
+    }
+
+    /// Reads `config.toml`, upgrading older layouts in memory. Also returns
+    /// the version the file was written in, or `None` if there is no file
+    /// yet and the defaults were returned.
+    fn read(&self) -> Result<(Config, Option<u32>)> {
+        if !self.config_path.exists() {
+            return Ok((Config::default(), None));
+        }
+
         let contents =
             fs::read_to_string(&self.config_path).context("Failed to read config file")?;
 
-        let config: Config = toml::from_str(&contents).context("Failed to parse config file")?;
+        let table: toml::Table =
+            toml::from_str(&contents).context("Failed to parse config file")?;
+        let (table, from_version) = upgrade(table)?;
+
+        let config: Config = table.try_into().context("Failed to parse config file")?;
+        config.check_exchange_rates()?;
+        config
+            .work_year
+            .check()
+            .context("Invalid [work_year] in config file")?;
 
-        Ok(config)
+        Ok((config, Some(from_version)))
     }
 
-    pub fn save(&self, config: &Config) -> Result<()> {
+    fn save(&self, config: &Config) -> Result<()> {
+        config.check_exchange_rates()?;
         let contents = toml::to_string_pretty(config).context("Failed to serialize config")?;
 
-        fs::write(&self.config_path, contents).context("Failed to write config file")?;
+        write_atomic(&self.config_path, contents.as_bytes())
+            .context("Failed to write config file")?;
 
         Ok(())
     }
 
-    pub fn set_rate(&self, role: Role, rate: f64) -> Result<()> {
-        let mut config = self.load()?;
-        config.rates.insert(role, rate);
-        self.save(&config)?;
-        Ok(())
+    /// Runs `f` holding the lock on the config directory, so concurrent
+    /// updates from separate terminals don't overwrite each other.
+    fn locked<R>(&self, f: impl FnOnce() -> Result<R>) -> Result<R> {
+        let dir = self.config_path.parent().unwrap_or_else(|| Path::new("."));
+        let _lock = DirLock::acquire(dir)?;
+        f()
+    }
+
+    /// Applies `change` to the config under the lock and saves the result,
+    /// keeping a backup if the file was upgraded.
+    fn update<R>(&self, change: impl FnOnce(&mut Config) -> Result<R>) -> Result<R> {
+        self.locked(|| {
+            let (mut config, from_version) = self.read()?;
+            let result = change(&mut config)?;
+            if let Some(from_version) = from_version
+                && from_version < CONFIG_SCHEMA_VERSION
+            {
+                backup_copy(&self.config_path, &format!("v{}.bak", from_version))?;
+            }
+            self.save(&config)?;
+            Ok(result)
+        })
+    }
+
+    /// Sets a role's rate from `from` onwards, in `currency` or else the
+    /// currency the role was paid in on that day. Earlier rates are kept, so
+    /// meetings before `from` are still priced at them.
+    pub fn set_rate(
+        &self,
+        role: &Role,
+        rate: Money,
+        currency: Option<Currency>,
+        from: NaiveDate,
+    ) -> Result<()> {
+        self.update(|config| {
+            let def = config
+                .roles
+                .iter_mut()
+                .find(|def| &def.id == role)
+                .ok_or_else(|| anyhow!("Unknown role: {}", role))?;
+            let currency = currency.unwrap_or_else(|| def.rate_on(from).1.clone());
+            def.set_rate_from(from, rate, currency);
+            Ok(())
+        })
+    }
+
+    pub fn add_role(&self, role: RoleDef) -> Result<()> {
+        self.update(|config| {
+            let mut names = vec![role.id.id(), role.name.as_str()];
+            names.extend(role.aliases.iter().map(String::as_str));
+            for name in names {
+                if let Ok(existing) = config.resolve_role(name) {
+                    return Err(anyhow!(
+                        "'{}' is already used by the {} role",
+                        name,
+                        config.role_name(&existing)
+                    ));
+                }
+            }
+
+            config.roles.push(role);
+            Ok(())
+        })
+    }
+
+    /// Removes a role that no person, template or series uses. Callers
+    /// check the saved meetings themselves.
+    pub fn remove_role(&self, role: &Role) -> Result<RoleDef> {
+        self.update(|config| {
+            self.check_role_unused(config, role)?;
+            let index = config
+                .roles
+                .iter()
+                .position(|def| &def.id == role)
+                .ok_or_else(|| anyhow!("Unknown role: {}", role))?;
+            Ok(config.roles.remove(index))
+        })
+    }
+
+    /// Declares `team`, replacing any team with the same name. Returns
+    /// whether one was replaced.
+    pub fn save_team(&self, team: Team) -> Result<bool> {
+        self.update(|config| {
+            Ok(match config.teams.iter_mut().find(|t| t.name == team.name) {
+                Some(existing) => {
+                    *existing = team;
+                    true
+                }
+                None => {
+                    config.teams.push(team);
+                    false
+                }
+            })
+        })
+    }
+
+    /// Removes a team. People on it are left without a team.
+    pub fn remove_team(&self, name: &str) -> Result<Team> {
+        self.update(|config| {
+            let name = config.resolve_team(name)?;
+            let index = config
+                .teams
+                .iter()
+                .position(|team| team.name == name)
+                .ok_or_else(|| anyhow!("Unknown team: {}", name))?;
+            let removed = config.teams.remove(index);
+            for person in &mut config.people {
+                if person.team.as_deref() == Some(name.as_str()) {
+                    person.team = None;
+                }
+            }
+            Ok(removed)
+        })
+    }
+
+    /// Adds `person` to the people directory, replacing anyone with the same
+    /// name. Returns whether someone was replaced.
+    pub fn save_person(&self, person: Person) -> Result<bool> {
+        self.update(|config| {
+            Ok(match config.people.iter_mut().find(|p| p.is_named(&person.name)) {
+                Some(existing) => {
+                    *existing = person;
+                    true
+                }
+                None => {
+                    config.people.push(person);
+                    false
+                }
+            })
+        })
+    }
+
+    pub fn remove_person(&self, name: &str) -> Result<Person> {
+        self.update(|config| {
+            let removed = config.resolve_person(name)?.clone();
+            config.people.retain(|p| p.name != removed.name);
+            Ok(removed)
+        })
+    }
+
+    /// Sets the overhead percentage for `role`, or the global one when `role`
+    /// is `None`. Clearing a role's percentage makes it use the global one.
+    pub fn set_overhead(&self, role: Option<&Role>, percent: Option<f64>) -> Result<()> {
+        self.update(|config| {
+            match role {
+                Some(role) => {
+                    let def = config
+                        .roles
+                        .iter_mut()
+                        .find(|def| &def.id == role)
+                        .ok_or_else(|| anyhow!("Unknown role: {}", role))?;
+                    def.overhead_percent = percent;
+                }
+                None => config.overhead_percent = percent.unwrap_or(0.0),
+            }
+            Ok(())
+        })
+    }
+
+    pub fn set_context_switch(&self, minutes: f64) -> Result<()> {
+        self.update(|config| {
+            config.context_switch_minutes = minutes;
+            Ok(())
+        })
+    }
+
+    pub fn set_rounding(&self, rounding: Rounding) -> Result<()> {
+        self.update(|config| {
+            config.rounding = rounding;
+            Ok(())
+        })
+    }
+
+    pub fn set_work_year(&self, work_year: WorkYear) -> Result<()> {
+        work_year.check()?;
+        self.update(|config| {
+            config.work_year = work_year;
+            Ok(())
+        })
+    }
+
+    pub fn set_currency(&self, currency: Currency) -> Result<()> {
+        self.update(|config| {
+            config.currency = currency;
+            Ok(())
+        })
+    }
+
+    pub fn set_locale(&self, locale: Locale) -> Result<()> {
+        self.update(|config| {
+            config.locale = locale;
+            Ok(())
+        })
+    }
+
+    /// Adds or replaces exchange rates, each the value of one unit in USD.
+    pub fn set_exchange_rates(&self, rates: &[(Currency, f64)]) -> Result<()> {
+        self.update(|config| {
+            for (currency, usd_value) in rates {
+                if currency.is_usd() {
+                    return Err(anyhow!("USD is the reference currency; its rate is always 1."));
+                }
+                if !usd_value.is_finite() || *usd_value <= 0.0 {
+                    return Err(anyhow!("Invalid exchange rate for {}: {}", currency, usd_value));
+                }
+                config.exchange_rates.insert(currency.clone(), *usd_value);
+            }
+            Ok(())
+        })
     }
 
+    pub fn remove_exchange_rate(&self, currency: &Currency) -> Result<f64> {
+        self.update(|config| {
+            config
+                .exchange_rates
+                .remove(currency)
+                .ok_or_else(|| anyhow!("No exchange rate is set for {}", currency))
+        })
+    }
+
+    pub fn set_storage(&self, backend: StorageBackend) -> Result<()> {
+        self.update(|config| {
+            config.storage = backend;
+            Ok(())
+        })
+    }
+
+    /// Puts the roles, their rates, overhead and context switch back to the
+    /// defaults. Everything else, including the storage backend, is kept.
+    /// Fails if a person, template or series uses a role that isn't built in.
     pub fn reset(&self) -> Result<()> {
-        let config = Config::default();
-        self.save(&config)?;
-        Ok(())
+        self.update(|config| {
+            let defaults = Config::default();
+            for def in &config.roles {
+                if defaults.role(&def.id).is_none() {
+                    self.check_role_unused(config, &def.id)?;
+                }
+            }
+            config.roles = defaults.roles;
+            config.overhead_percent = defaults.overhead_percent;
+            config.context_switch_minutes = defaults.context_switch_minutes;
+            Ok(())
+        })
+    }
+
+    /// Fails if anyone in the people directory, a template or a series
+    /// still uses `role`, since it couldn't be priced without it.
+    fn check_role_unused(&self, config: &Config, role: &Role) -> Result<()> {
+        let mut users: Vec<String> = config
+            .people
+            .iter()
+            .filter(|person| &person.role == role)
+            .map(|person| person.name.clone())
+            .collect();
+        users.extend(
+            self.load_templates()?
+                .into_iter()
+                .filter(|template| template.attendees.contains_key(role))
+                .map(|template| format!("the {} template", template.name)),
+        );
+        users.extend(
+            self.load_series()?
+                .into_iter()
+                .filter(|series| series.attendees.contains_key(role))
+                .map(|series| format!("the {} series", series.name)),
+        );
+
+        if users.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow!(
+            "The {} role is still used by {}. Move them to another role first.",
+            config.role_name(role),
+            users.join(", ")
+        ))
     }
 
     pub fn config_path(&self) -> &PathBuf {
         &self.config_path
     }
+
+    /// Templates live next to `config.toml` in `templates.toml`.
+    pub fn templates_path(&self) -> PathBuf {
+        self.config_path.with_file_name("templates.toml")
+    }
+
+    pub fn load_templates(&self) -> Result<Vec<Template>> {
+        load_list(&self.templates_path(), "templates", TEMPLATES_SCHEMA_VERSION)
+    }
+
+    fn save_templates(&self, templates: Vec<Template>) -> Result<()> {
+        save_list(&self.templates_path(), "templates", TEMPLATES_SCHEMA_VERSION, &templates)
+    }
+
+    /// Looks up a template by name, listing the known ones if it's missing.
+    pub fn template(&self, name: &str) -> Result<Template> {
+        let templates = self.load_templates()?;
+        let name = Meeting::normalize_label(name);
+        let known: Vec<String> = templates.iter().map(|t| t.name.clone()).collect();
+
+        templates
+            .into_iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| {
+                if known.is_empty() {
+                    anyhow!("Unknown template: {}. Create one with `meeting-meter template add`.", name)
+                } else {
+                    anyhow!("Unknown template: {}. Known templates: {}", name, known.join(", "))
+                }
+            })
+    }
+
+    /// Adds `template`, replacing any existing one with the same name.
+    /// Returns whether one was replaced.
+    pub fn save_template(&self, template: Template) -> Result<bool> {
+        self.locked(|| {
+            let mut templates = self.load_templates()?;
+            let replaced = match templates.iter_mut().find(|t| t.name == template.name) {
+                Some(existing) => {
+                    *existing = template;
+                    true
+                }
+                None => {
+                    templates.push(template);
+                    false
+                }
+            };
+            self.save_templates(templates)?;
+            Ok(replaced)
+        })
+    }
+
+    pub fn remove_template(&self, name: &str) -> Result<Template> {
+        self.locked(|| {
+            let removed = self.template(name)?;
+            let mut templates = self.load_templates()?;
+            templates.retain(|t| t.name != removed.name);
+            self.save_templates(templates)?;
+            Ok(removed)
+        })
+    }
+
+    /// Recurring series live next to `config.toml` in `series.toml`.
+    pub fn series_path(&self) -> PathBuf {
+        self.config_path.with_file_name("series.toml")
+    }
+
+    pub fn load_series(&self) -> Result<Vec<Series>> {
+        load_list(&self.series_path(), "series", SERIES_SCHEMA_VERSION)
+    }
+
+    /// Applies `change` to the series under the lock and saves them.
+    pub fn update_series<R>(&self, change: impl FnOnce(&mut Vec<Series>) -> Result<R>) -> Result<R> {
+        self.locked(|| {
+            let mut series = self.load_series()?;
+            let result = change(&mut series)?;
+            save_list(&self.series_path(), "series", SERIES_SCHEMA_VERSION, &series)?;
+            Ok(result)
+        })
+    }
+}
+
+/// Parses an exchange-rate file: one `CODE VALUE` pair per line, where the
+/// value is one unit of the currency in USD. Commas or `=` may separate the
+/// two, `#` starts a comment, and a `currency,rate` header line is skipped.
+pub fn parse_exchange_rates(contents: &str) -> Result<Vec<(Currency, f64)>> {
+    let mut rates = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = line
+            .split(|c: char| c == ',' || c == '=' || c.is_whitespace())
+            .filter(|field| !field.is_empty())
+            .collect();
+        let [code, value] = fields.as_slice() else {
+            return Err(anyhow!("Line {}: expected a currency and a rate, e.g. 'EUR 1.08'", index + 1));
+        };
+        let Ok(value) = value.parse::<f64>() else {
+            if rates.is_empty() && code.parse::<Currency>().is_err() {
+                continue;
+            }
+            return Err(anyhow!("Line {}: invalid rate '{}'", index + 1, value));
+        };
+        let currency: Currency = code.parse().with_context(|| format!("Line {}", index + 1))?;
+        rates.push((currency, value));
+    }
+    Ok(rates)
+}
+
+/// Reads the `key` array of tables from a versioned TOML file such as
+/// `templates.toml`. A missing file holds no entries.
+fn load_list<T: DeserializeOwned>(path: &Path, key: &str, version: u32) -> Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let name = path.display();
+    let contents = fs::read_to_string(path).with_context(|| format!("Failed to read {}", name))?;
+    let mut table: toml::Table =
+        toml::from_str(&contents).with_context(|| format!("Failed to parse {}", name))?;
+
+    let file_version = match table.get("schema_version") {
+        Some(value) => value
+            .as_integer()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| anyhow!("Invalid schema_version in {}", name))?,
+        None => version,
+    };
+    if file_version > version {
+        return Err(anyhow!(
+            "{} uses schema version {}, but this meeting-meter only understands up to {}. Please upgrade meeting-meter.",
+            name,
+            file_version,
+            version
+        ));
+    }
+
+    match table.remove(key) {
+        Some(items) => items
+            .try_into()
+            .with_context(|| format!("Failed to parse {}", name)),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn save_list<T: Serialize>(path: &Path, key: &str, version: u32, items: &[T]) -> Result<()> {
+    // Serialized directly rather than through `toml::Table`, which would sort
+    // each entry's fields alphabetically.
+    #[derive(Serialize)]
+    struct ListFile<'a, T> {
+        schema_version: u32,
+        #[serde(flatten)]
+        items: BTreeMap<&'a str, &'a [T]>,
+    }
+
+    let file = ListFile {
+        schema_version: version,
+        items: BTreeMap::from([(key, items)]),
+    };
+    let contents = toml::to_string_pretty(&file)
+        .with_context(|| format!("Failed to serialize {}", key))?;
+
+    write_atomic(path, contents.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Brings a parsed config up to `CONFIG_SCHEMA_VERSION`, returning it along
+/// with the version it started at.
+fn upgrade(mut table: toml::Table) -> Result<(toml::Table, u32)> {
+    let from_version = match table.get("schema_version") {
+        Some(value) => value
+            .as_integer()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| anyhow!("Invalid schema_version in config file"))?,
+        None => 1,
+    };
+
+    if from_version > CONFIG_SCHEMA_VERSION {
+        return Err(anyhow!(
+            "Config file uses schema version {}, but this meeting-meter only understands up to {}. Please upgrade meeting-meter.",
+            from_version,
+            CONFIG_SCHEMA_VERSION
+        ));
+    }
+
+    for version in from_version.max(1)..CONFIG_SCHEMA_VERSION {
+        MIGRATIONS[(version - 1) as usize](&mut table)
+            .with_context(|| format!("Failed to upgrade config from version {}", version))?;
+    }
+    table.insert(
+        "schema_version".to_string(),
+        toml::Value::Integer(CONFIG_SCHEMA_VERSION as i64),
+    );
+
+    Ok((table, from_version))
+}
+
+/// Turns the `[rates]` table into `[[roles]]` entries, starting from the
+/// built-in roles so their names and aliases are kept.
+fn migrate_v1_to_v2(table: &mut toml::Table) -> Result<()> {
+    if table.contains_key("roles") {
+        return Ok(());
+    }
+
+    let mut roles = default_roles();
+    if let Some(toml::Value::Table(rates)) = table.remove("rates") {
+        for (id, rate) in rates {
+            let rate = rate
+                .as_float()
+                .or_else(|| rate.as_integer().map(|r| r as f64))
+                .map(Money::from_f64)
+                .ok_or_else(|| anyhow!("Invalid rate for role {}", id))?;
+            match roles.iter_mut().find(|def| def.id.id() == id) {
+                Some(def) => def.rate = rate,
+                None => roles.push(RoleDef {
+                    id: Role::new(&id),
+                    name: id.clone(),
+                    aliases: Vec::new(),
+                    rate,
+                    currency: Currency::usd(),
+                    overhead_percent: None,
+                    rate_changes: Vec::new(),
+                }),
+            }
+        }
+    }
+
+    table.insert("roles".to_string(), toml::Value::try_from(roles)?);
+    Ok(())
+}
+
+/// The benefits flag stood for a fixed 40% overhead. A context-switch
+/// multiplier `m` becomes the minutes that add the same cost to a one-hour
+/// meeting, `(m - 1) * 60`.
+fn migrate_v2_to_v3(table: &mut toml::Table) -> Result<()> {
+    let include_benefits = match table.remove("include_benefits") {
+        Some(value) => value
+            .as_bool()
+            .ok_or_else(|| anyhow!("Invalid include_benefits in config file"))?,
+        None => false,
+    };
+    // A missing multiplier meant 1.0 to everyone but serde, which read it as 0.0.
+    let multiplier = match table.remove("context_switch_multiplier") {
+        Some(value) => value
+            .as_float()
+            .or_else(|| value.as_integer().map(|m| m as f64))
+            .ok_or_else(|| anyhow!("Invalid context_switch_multiplier in config file"))?,
+        None => 1.0,
+    };
+
+    table.insert(
+        "overhead_percent".to_string(),
+        toml::Value::Float(if include_benefits { 40.0 } else { 0.0 }),
+    );
+    table.insert(
+        "context_switch_minutes".to_string(),
+        toml::Value::Float(((multiplier - 1.0) * 60.0).max(0.0)),
+    );
+    Ok(())
+}
+
+/// Only new optional settings, so the config loads as it is.
+fn migrate_v3_to_v4(_table: &mut toml::Table) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_fixture(contents: &str) -> (Config, u32) {
+        let table: toml::Table = toml::from_str(contents).unwrap();
+        let (table, from_version) = upgrade(table).unwrap();
+        (table.try_into().unwrap(), from_version)
+    }
+
+    #[test]
+    fn test_load_v1_config() {
+        let (config, from_version) = load_fixture(include_str!("../tests/fixtures/config_v1.toml"));
+
+        assert_eq!(from_version, 1);
+        assert_eq!(config.schema_version, CONFIG_SCHEMA_VERSION);
+        assert_eq!(config.roles.len(), default_roles().len());
+        assert_eq!(config.get_rate(&Role::new("engineer")).unwrap(), Money::dollars(120));
+        assert_eq!(config.get_rate(&Role::new("vp")).unwrap(), Money::dollars(300));
+        assert_eq!(config.role_name(&Role::new("vp")), "VP");
+        assert_eq!(config.resolve_role("pm").unwrap(), Role::new("product_manager"));
+    }
+
+    #[test]
+    fn test_load_v2_config() {
+        let (config, from_version) = load_fixture(include_str!("../tests/fixtures/config_v2.toml"));
+
+        assert_eq!(from_version, 2);
+        assert_eq!(config.overhead_percent, 40.0);
+        assert_eq!(config.context_switch_minutes, 0.0);
+        assert_eq!(config.storage, StorageBackend::Sqlite);
+        assert_eq!(config.roles.len(), 2);
+        assert_eq!(config.resolve_role("ops").unwrap(), Role::new("sre"));
+    }
+
+    #[test]
+    fn test_load_v3_config() {
+        let (config, from_version) = load_fixture(include_str!("../tests/fixtures/config_v3.toml"));
+
+        assert_eq!(from_version, 3);
+        assert_eq!(config.overhead_percent, 30.0);
+        assert_eq!(config.context_switch_minutes, 10.0);
+        assert_eq!(config.overhead_percent(&Role::new("engineer")), 30.0);
+        assert_eq!(config.overhead_percent(&Role::new("contractor")), 0.0);
+    }
+
+    #[test]
+    fn test_load_v4_config() {
+        let (config, from_version) = load_fixture(include_str!("../tests/fixtures/config_v4.toml"));
+
+        assert_eq!(from_version, 4);
+        assert_eq!(config.rounding, Rounding::QuarterHour);
+        assert_eq!(config.currency.code(), "EUR");
+        assert_eq!(config.work_year.holidays, 12);
+        assert_eq!(config.role(&Role::new("engineer")).unwrap().rate_changes.len(), 1);
+        assert_eq!(config.team("platform").unwrap().department.as_deref(), Some("engineering"));
+        assert_eq!(config.person("Priya").unwrap().rate, Some(Money::dollars(86)));
+    }
+
+    #[test]
+    fn test_context_switch_multiplier_becomes_minutes() {
+        let (config, _) = load_fixture("schema_version = 2\ncontext_switch_multiplier = 1.25\n");
+        assert_eq!(config.context_switch_minutes, 15.0);
+        assert_eq!(config.overhead_percent, 0.0);
+    }
+
+    #[test]
+    fn test_load_rejects_a_year_without_working_hours() {
+        let dir = std::env::temp_dir().join(format!("meeting-meter-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let manager = ConfigManager {
+            config_path: dir.join("config.toml"),
+        };
+        fs::write(
+            &manager.config_path,
+            format!("schema_version = {}\n\n[work_year]\nholidays = 300\n", CONFIG_SCHEMA_VERSION),
+        )
+        .unwrap();
+
+        assert!(manager.load().is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_templates_round_trip() {
+        let dir = std::env::temp_dir().join(format!("meeting-meter-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let manager = ConfigManager {
+            config_path: dir.join("config.toml"),
+        };
+
+        let standup = Template {
+            name: "standup".to_string(),
+            duration_minutes: Some(15),
+            title: None,
+            category: Some("standup".to_string()),
+            tags: vec!["standup".to_string()],
+            attendees: [(Role::new("engineer"), 5), (Role::new("senior_pm"), 1)].into(),
+        };
+        assert!(!manager.save_template(standup.clone()).unwrap());
+        assert!(manager.save_template(standup.clone()).unwrap());
+
+        assert_eq!(manager.template("Standup").unwrap(), standup);
+        assert!(manager.template("retro").is_err());
+
+        manager.remove_template("standup").unwrap();
+        assert!(manager.load_templates().unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_exchange_rates() {
+        let rates = parse_exchange_rates(
+            "currency,rate\n# from the finance sheet\nEUR,1.08\ngbp = 1.27  # October\n\nJPY 0.0067\n",
+        )
+        .unwrap();
+        let codes: Vec<&str> = rates.iter().map(|(currency, _)| currency.code()).collect();
+        assert_eq!(codes, vec!["EUR", "GBP", "JPY"]);
+        assert_eq!(rates[1].1, 1.27);
+        assert!(parse_exchange_rates("EUR 1.08\nGBP lots\n").is_err());
+        assert!(parse_exchange_rates("EUR\n").is_err());
+
+        let dir = std::env::temp_dir().join(format!("meeting-meter-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let manager = ConfigManager {
+            config_path: dir.join("config.toml"),
+        };
+        let eur: Currency = "EUR".parse().unwrap();
+
+        // Rates can't be switched to a currency that has no exchange rate.
+        assert!(manager.set_currency(eur.clone()).is_err());
+        manager.set_exchange_rates(&rates).unwrap();
+        manager.set_currency(eur.clone()).unwrap();
+        assert_eq!(manager.load().unwrap().currency, eur);
+        assert!(manager.remove_exchange_rate(&eur).is_err());
+        assert!(manager.set_exchange_rates(&[(Currency::usd(), 1.0)]).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reset_keeps_storage_and_people() {
+        let dir = std::env::temp_dir().join(format!("meeting-meter-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let manager = ConfigManager {
+            config_path: dir.join("config.toml"),
+        };
+        let engineer = Role::new("engineer");
+
+        manager.set_storage(StorageBackend::Sqlite).unwrap();
+        manager.set_overhead(None, Some(40.0)).unwrap();
+        manager
+            .set_rate(&engineer, Money::dollars(200), None, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap())
+            .unwrap();
+        manager
+            .save_person(Person {
+                name: "Priya".to_string(),
+                role: engineer.clone(),
+                rate: None,
+                currency: Currency::usd(),
+                team: None,
+            })
+            .unwrap();
+
+        manager.reset().unwrap();
+        let config = manager.load().unwrap();
+        assert_eq!(config.storage, StorageBackend::Sqlite);
+        assert!(config.person("Priya").is_some());
+        assert_eq!(config.overhead_percent, 0.0);
+        assert_eq!(config.roles, default_roles());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_roles_in_use_are_not_removed() {
+        let dir = std::env::temp_dir().join(format!("meeting-meter-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let manager = ConfigManager {
+            config_path: dir.join("config.toml"),
+        };
+        let sre = Role::new("sre");
+        manager
+            .add_role(RoleDef::new("sre", "SRE", &[], Money::dollars(80)))
+            .unwrap();
+        manager
+            .save_person(Person {
+                name: "Sam".to_string(),
+                role: sre.clone(),
+                rate: None,
+                currency: Currency::usd(),
+                team: None,
+            })
+            .unwrap();
+
+        assert!(manager.remove_role(&sre).is_err());
+        assert!(manager.reset().is_err());
+
+        manager.remove_person("Sam").unwrap();
+        manager.remove_role(&sre).unwrap();
+        assert!(manager.load().unwrap().role(&sre).is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_all_kept() {
+        let dir = std::env::temp_dir().join(format!("meeting-meter-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.toml");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let manager = ConfigManager {
+                    config_path: config_path.clone(),
+                };
+                std::thread::spawn(move || {
+                    manager
+                        .save_person(Person {
+                            name: format!("Person {}", i),
+                            role: Role::new("engineer"),
+                            rate: None,
+                            currency: Currency::usd(),
+                            team: None,
+                        })
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let manager = ConfigManager { config_path };
+        assert_eq!(manager.load().unwrap().people.len(), 8);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_newer_config() {
+        let table: toml::Table = toml::from_str("schema_version = 99").unwrap();
+        assert!(upgrade(table).is_err());
+    }
 }
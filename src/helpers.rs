@@ -1,73 +1,179 @@
-use crate::models::Role;
+use crate::cli::LabelArgs;
+use crate::models::{Attendance, Config, Meeting, Participant, Person, Role, Template};
+use crate::money::Money;
+use crate::time_parser;
+use anyhow::{Result, anyhow};
 use chrono::Duration;
 use std::collections::HashMap;
 
-pub fn parse_attendees(
-    engineers: u32,
-    senior_engineers: u32,
-    staff_engineers: u32,
-    principal_engineers: u32,
-    pms: u32,
-    senior_pms: u32,
-    director_pms: u32,
-    designers: u32,
-    senior_designers: u32,
-    analysts: u32,
-    senior_analysts: u32,
-    directors: u32,
-    vps: u32,
-    executives: u32,
-    generic: u32,
-) -> HashMap<Role, u32> {
-    let mut attendees = HashMap::new();
+/// Parses repeated `role=count` attendee specs into a head count per role.
+///
+/// Roles are resolved against the configured roles and their aliases. A bare
+/// role counts as one attendee, and repeating a role adds to its count.
+pub fn parse_attendees(specs: &[String], config: &Config) -> Result<HashMap<Role, u32>> {
+    let mut attendees = parse_counts(specs, config)?;
+    attendees.retain(|_, count| *count > 0);
+    Ok(attendees)
+}
 
-    if engineers > 0 {
-        attendees.insert(Role::Engineer, engineers);
-    }
-    if senior_engineers > 0 {
-        attendees.insert(Role::SeniorEngineer, senior_engineers);
-    }
-    if staff_engineers > 0 {
-        attendees.insert(Role::StaffEngineer, staff_engineers);
-    }
-    if principal_engineers > 0 {
-        attendees.insert(Role::PrincipalEngineer, principal_engineers);
-    }
-    if pms > 0 {
-        attendees.insert(Role::ProductManager, pms);
+/// Applies `role=count` specs on top of a template's attendee mix. Each role
+/// given replaces the template's count for that role, and `role=0` drops it.
+pub fn override_attendees(
+    base: &mut HashMap<Role, u32>,
+    specs: &[String],
+    config: &Config,
+) -> Result<()> {
+    for (role, count) in parse_counts(specs, config)? {
+        if count == 0 {
+            base.remove(&role);
+        } else {
+            base.insert(role, count);
+        }
     }
-    if senior_pms > 0 {
-        attendees.insert(Role::SeniorPm, senior_pms);
-    }
-    if director_pms > 0 {
-        attendees.insert(Role::DirectorPm, director_pms);
-    }
-    if designers > 0 {
-        attendees.insert(Role::Designer, designers);
-    }
-    if senior_designers > 0 {
-        attendees.insert(Role::SeniorDesigner, senior_designers);
+    Ok(())
+}
+
+/// The attendee mix for a `start` or `log` invocation: the template's, if
+/// one was given, with `specs` applied on top.
+pub fn resolve_attendees(
+    template: Option<&Template>,
+    specs: &[String],
+    config: &Config,
+) -> Result<HashMap<Role, u32>> {
+    let mut attendees = HashMap::new();
+    if let Some(template) = template {
+        for (role, count) in &template.attendees {
+            if config.role(role).is_none() {
+                return Err(anyhow!(
+                    "Template '{}' uses the role '{}', which is no longer configured.",
+                    template.name,
+                    role
+                ));
+            }
+            attendees.insert(role.clone(), *count);
+        }
     }
-    if analysts > 0 {
-        attendees.insert(Role::Analyst, analysts);
+    override_attendees(&mut attendees, specs, config)?;
+    Ok(attendees)
+}
+
+/// Resolves `--with` names against the people directory. Repeated names
+/// are only counted once.
+pub fn resolve_people(names: &[String], config: &Config) -> Result<Vec<Participant>> {
+    let mut people: Vec<Participant> = Vec::new();
+    for name in names.iter().filter(|name| !name.trim().is_empty()) {
+        let person = config.resolve_person(name)?;
+        if !people.iter().any(|p| p.name == person.name) {
+            people.push(Participant {
+                name: person.name.clone(),
+                role: person.role.clone(),
+                team: person.team.clone(),
+            });
+        }
     }
-    if senior_analysts > 0 {
-        attendees.insert(Role::SeniorAnalyst, senior_analysts);
+    Ok(people)
+}
+
+/// Counts each of `people` as one more attendee of their role.
+pub fn add_people(attendees: &mut HashMap<Role, u32>, people: &[Participant]) -> Result<()> {
+    for participant in people {
+        add_count(attendees, participant.role.clone(), 1)?;
     }
-    if directors > 0 {
-        attendees.insert(Role::Director, directors);
+    Ok(())
+}
+
+fn add_count(attendees: &mut HashMap<Role, u32>, role: Role, count: u32) -> Result<()> {
+    let total = attendees.entry(role).or_insert(0);
+    *total = total
+        .checked_add(count)
+        .ok_or_else(|| anyhow!("Attendee count too large: more than {} in one role.", u32::MAX))?;
+    Ok(())
+}
+
+/// Splits the attendance time off specs such as `pm=1@14:30-15:00` or
+/// `priya@14:30`. Returns every spec without its time, to be counted as
+/// usual, and the specs that had one alongside it.
+pub fn split_windows(specs: &[String]) -> (Vec<String>, Vec<(String, String)>) {
+    let mut plain = Vec::new();
+    let mut windows = Vec::new();
+    for spec in specs {
+        match spec.split_once('@') {
+            Some((spec, window)) => {
+                plain.push(spec.to_string());
+                windows.push((spec.to_string(), window.to_string()));
+            }
+            None => plain.push(spec.clone()),
+        }
     }
-    if vps > 0 {
-        attendees.insert(Role::Vp, vps);
+    (plain, windows)
+}
+
+/// Adds the attendance times split off `--attendee` and `--with` specs to
+/// `meeting`, then checks that all of its attendance fits it.
+pub fn add_attendance(
+    meeting: &mut Meeting,
+    attendees: &[(String, String)],
+    people: &[(String, String)],
+    config: &Config,
+) -> Result<()> {
+    for (spec, window) in attendees {
+        let (role, count) = parse_attendee(spec, config)?;
+        if count == 0 {
+            continue;
+        }
+        let (joined, left) = time_parser::parse_window(window, meeting.start_time, meeting.end_time)?;
+        meeting.attendance.push(Attendance {
+            role,
+            count,
+            name: None,
+            joined,
+            left,
+        });
     }
-    if executives > 0 {
-        attendees.insert(Role::Executive, executives);
+    for (name, window) in people {
+        let person = config.resolve_person(name)?;
+        let (joined, left) = time_parser::parse_window(window, meeting.start_time, meeting.end_time)?;
+        meeting.attendance.push(Attendance {
+            role: person.role.clone(),
+            count: 1,
+            name: Some(person.name.clone()),
+            joined,
+            left,
+        });
     }
-    if generic > 0 {
-        attendees.insert(Role::Generic, generic);
+    meeting.check_attendance()
+}
+
+/// Like `parse_attendees`, but keeps roles given a count of zero.
+fn parse_counts(specs: &[String], config: &Config) -> Result<HashMap<Role, u32>> {
+    let mut attendees = HashMap::new();
+
+    for spec in specs {
+        let (role, count) = parse_attendee(spec, config)?;
+        add_count(&mut attendees, role, count)?;
     }
 
-    attendees
+    Ok(attendees)
+}
+
+fn parse_attendee(spec: &str, config: &Config) -> Result<(Role, u32)> {
+    let (role_str, count_str) = match spec.split_once('=') {
+        Some((role, count)) => (role.trim(), Some(count.trim())),
+        None => (spec.trim(), None),
+    };
+
+    let role = config.resolve_role(role_str)?;
+    let count = match count_str {
+        Some(count) => count.parse::<u32>().map_err(|_| {
+            anyhow!(
+                "Invalid attendee count in '{}'. Use the form role=count, e.g. 'engineer=3'.",
+                spec
+            )
+        })?,
+        None => 1,
+    };
+
+    Ok((role, count))
 }
 
 pub fn display_duration(duration: &Duration) -> String {
@@ -76,23 +182,257 @@ pub fn display_duration(duration: &Duration) -> String {
 
     if hours > 0 {
         format!("{}h {}m", hours, minutes)
-    } else {
+    } else if minutes > 0 {
         format!("{}m", minutes)
+    } else {
+        format!("{}s", duration.num_seconds().max(0))
+    }
+}
+
+/// Formats an amount in the display currency.
+pub fn display_cost(amount: Money, config: &Config) -> String {
+    config.format_money(amount, &config.currency)
+}
+
+/// Formats a meeting's cost in the currency it was priced in.
+pub fn display_meeting_cost(meeting: &Meeting, config: &Config) -> String {
+    config.format_money(meeting.cost, &meeting.currency)
+}
+
+/// A role's hourly rate today in its own currency, e.g. "€54.00/hr".
+pub fn display_rate(role: &Role, config: &Config) -> String {
+    match config.role(role) {
+        Some(def) => {
+            let (rate, currency) = def.rate_on(chrono::Local::now().date_naive());
+            format!("{}/hr", config.format_money(rate, currency))
+        }
+        None => format!("{}/hr", display_cost(Money::ZERO, config)),
+    }
+}
+
+/// A person's hourly rate in its own currency: theirs if the directory
+/// gives them one, else their role's.
+pub fn display_person_rate(person: &Person, config: &Config) -> String {
+    match person.rate {
+        Some(rate) => format!("{}/hr", config.format_money(rate, &person.currency)),
+        None => display_rate(&person.role, config),
     }
 }
 
-pub fn display_cost(amount: f64) -> String {
-    format!("${:.2}", amount)
+/// Names of a meeting's named attendees, e.g. "Priya, Sam". Empty if it has none.
+pub fn display_people(people: &[Participant]) -> String {
+    people
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Condensed one-line attendee list, e.g. "3x Engineer, 1x Product Manager".
+pub fn summarize_attendees(attendees: &HashMap<Role, u32>, config: &Config) -> String {
+    let mut entries: Vec<_> = attendees
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .map(|(role, count)| (config.role_name(role), *count))
+        .collect();
+    entries.sort_by(|(name_a, count_a), (name_b, count_b)| {
+        count_b.cmp(count_a).then_with(|| name_a.cmp(name_b))
+    });
+
+    entries
+        .iter()
+        .map(|(name, count)| format!("{}x {}", count, name))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-pub fn display_attendees(attendees: &HashMap<Role, u32>, rates: &HashMap<Role, f64>) {
+pub fn display_attendees(attendees: &HashMap<Role, u32>, config: &Config) {
     for (role, count) in attendees {
-        let rate = rates.get(role).unwrap_or(&0.0);
         println!(
-            "  {}x {:<18} @ ${:<6}/hr",
+            "  {}x {:<18} @ {}",
             count,
-            role.to_string(),
-            rate
+            config.role_name(role),
+            display_rate(role, config)
+        );
+    }
+}
+
+/// The time billed for `meeting`, with the wall-clock time as well if it
+/// was paused, e.g. "50m billed, 1h 0m wall clock".
+pub fn display_billed_duration(meeting: &Meeting) -> String {
+    let billed = meeting.billed_duration();
+    let wall_clock = meeting.wall_clock_duration();
+    if billed == wall_clock {
+        display_duration(&billed)
+    } else {
+        format!(
+            "{} billed, {} wall clock",
+            display_duration(&billed),
+            display_duration(&wall_clock)
+        )
+    }
+}
+
+/// Prints everything we know about one meeting, as shown by `log`, `show` and `edit`.
+pub fn print_meeting_details(meeting: &Meeting, config: &Config) {
+    println!("Meeting Details:");
+    println!("  ID:       {}", meeting.id);
+    if let Some(title) = &meeting.title {
+        println!("  Title:    {}", title);
+    }
+    if let Some(category) = &meeting.category {
+        println!("  Category: {}", category);
+    }
+    if let Some(team) = &meeting.team {
+        println!("  Team:     {}", team);
+    }
+    if !meeting.tags.is_empty() {
+        println!("  Tags:     {}", meeting.tags.join(", "));
+    }
+    println!("  Date:     {}", meeting.start_time.format("%a %Y-%m-%d"));
+    println!("  Start:    {}", meeting.start_time.format("%-I:%M %p"));
+    if meeting.end_time.date_naive() == meeting.start_time.date_naive() {
+        println!("  End:      {}", meeting.end_time.format("%-I:%M %p"));
+    } else {
+        println!("  End:      {}", meeting.end_time.format("%a %Y-%m-%d %-I:%M %p"));
+    }
+    println!("  Duration: {}", display_billed_duration(meeting));
+    for pause in &meeting.pauses {
+        println!(
+            "  Paused:   {} - {}",
+            pause.paused.format("%-I:%M %p"),
+            pause.resumed.format("%-I:%M %p")
+        );
+    }
+    if let Some(notes) = &meeting.notes {
+        println!("  Notes:    {}", notes);
+    }
+    println!();
+    println!("Attendees:");
+    display_attendees(&meeting.attendees, config);
+    if !meeting.people.is_empty() {
+        println!("  Including: {}", display_people(&meeting.people));
+    }
+    for part in &meeting.attendance {
+        let who = match &part.name {
+            Some(name) => name.clone(),
+            None => format!("{}x {}", part.count, config.role_name(&part.role)),
+        };
+        println!(
+            "  Only {} - {}: {}",
+            part.joined.format("%-I:%M %p"),
+            part.left.format("%-I:%M %p"),
+            who
+        );
+    }
+    println!();
+    println!("Total Cost: {}", display_meeting_cost(meeting, config));
+}
+
+/// Applies `--title`, `--category` and `--tag` to `meeting`. Only the labels
+/// that were given are changed, and an empty title or category clears it.
+pub fn apply_labels(meeting: &mut Meeting, labels: LabelArgs) {
+    if let Some(title) = labels.title {
+        meeting.title = Some(title.trim().to_string()).filter(|t| !t.is_empty());
+    }
+    if let Some(category) = labels.category {
+        meeting.category = Some(Meeting::normalize_label(&category)).filter(|c| !c.is_empty());
+    }
+    if !labels.tags.is_empty() {
+        meeting.tags = Meeting::parse_tags(&labels.tags);
+    }
+}
+
+/// One-line summary of a meeting's title, category and tags, e.g.
+/// `Sprint planning [planning] #q3-launch`. Empty if it has none.
+pub fn display_labels(meeting: &Meeting) -> String {
+    let mut parts = Vec::new();
+    if let Some(title) = &meeting.title {
+        parts.push(title.clone());
+    }
+    if let Some(category) = &meeting.category {
+        parts.push(format!("[{}]", category));
+    }
+    parts.extend(meeting.tags.iter().map(|tag| format!("#{}", tag)));
+    parts.join(" ")
+}
+
+/// The short form of a meeting id shown in listings; any unique prefix is
+/// accepted back by `show`, `edit` and `delete`.
+pub fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(values: &[&str]) -> Result<HashMap<Role, u32>> {
+        let specs: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        parse_attendees(&specs, &Config::default())
+    }
+
+    #[test]
+    fn test_parse_attendees() {
+        let attendees =
+            parse(&["senior_engineer=3", "pm=1", "eng", "engineer=2"]).unwrap();
+        assert_eq!(attendees.get(&Role::new("senior_engineer")), Some(&3));
+        assert_eq!(attendees.get(&Role::new("product_manager")), Some(&1));
+        assert_eq!(attendees.get(&Role::new("engineer")), Some(&3));
+    }
+
+    #[test]
+    fn test_parse_attendees_skips_zero_counts() {
+        let attendees = parse(&["vp=0"]).unwrap();
+        assert!(attendees.is_empty());
+    }
+
+    #[test]
+    fn test_parse_attendees_rejects_bad_input() {
+        assert!(parse(&["wizard=2"]).is_err());
+        assert!(parse(&["engineer=lots"]).is_err());
+        assert!(parse(&["engineer=-1"]).is_err());
+        assert!(parse(&["engineer=4294967295", "engineer=1"]).is_err());
+    }
+
+    #[test]
+    fn test_override_attendees() {
+        let mut attendees: HashMap<Role, u32> =
+            [(Role::new("engineer"), 5), (Role::new("senior_pm"), 1)].into();
+        let specs: Vec<String> = ["eng=3", "senior_pm=0", "designer"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        override_attendees(&mut attendees, &specs, &Config::default()).unwrap();
+        assert_eq!(attendees.get(&Role::new("engineer")), Some(&3));
+        assert_eq!(attendees.get(&Role::new("senior_pm")), None);
+        assert_eq!(attendees.get(&Role::new("designer")), Some(&1));
+    }
+
+    #[test]
+    fn test_apply_labels() {
+        let start = chrono::Local::now();
+        let mut meeting = Meeting::new(start, start, HashMap::new(), Money::ZERO);
+        apply_labels(
+            &mut meeting,
+            LabelArgs {
+                title: Some(" Sprint planning ".to_string()),
+                category: Some("Planning".to_string()),
+                tags: vec!["q3-launch".to_string()],
+            },
+        );
+        assert_eq!(display_labels(&meeting), "Sprint planning [planning] #q3-launch");
+
+        // Only what's given changes, and empty values clear.
+        apply_labels(
+            &mut meeting,
+            LabelArgs {
+                title: Some(String::new()),
+                category: None,
+                tags: Vec::new(),
+            },
         );
+        assert_eq!(display_labels(&meeting), "[planning] #q3-launch");
     }
 }
@@ -4,114 +4,249 @@ mod commands;
 mod config;
 mod helpers;
 mod models;
+mod money;
+mod reports;
+mod series;
 mod storage;
 mod time_parser;
 mod ui;
 
 use anyhow::Result;
 use clap::Parser;
-use cli::{Cli, Commands, ConfigCommands};
+use cli::{
+    Cli, Commands, ConfigCommands, ExchangeRateCommands, PeopleCommands, SeriesCommands,
+    SetCommands, StorageCommands, TeamCommands, TemplateCommands, TrashCommands,
+};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
         Commands::Start {
-            engineers,
-            senior_engineers,
-            staff_engineers,
-            principal_engineers,
-            pms,
-            senior_pms,
-            director_pms,
-            designers,
-            senior_designers,
-            analysts,
-            senior_analysts,
-            directors,
-            vps,
-            executives,
-            generic,
+            attendees,
+            people,
+            team,
+            template,
+            labels,
         } => {
-            commands::start::run(
-                engineers,
-                senior_engineers,
-                staff_engineers,
-                principal_engineers,
-                pms,
-                senior_pms,
-                director_pms,
-                designers,
-                senior_designers,
-                analysts,
-                senior_analysts,
-                directors,
-                vps,
-                executives,
-                generic,
-            )?;
+            commands::start::run(attendees, people, team, template, labels)?;
         }
 
-        Commands::Log {
-            start,
-            end,
+        Commands::Log(args) => {
+            commands::log::run(args)?;
+        }
+
+        Commands::Estimate {
             duration,
-            engineers,
-            senior_engineers,
-            staff_engineers,
-            principal_engineers,
-            pms,
-            senior_pms,
-            director_pms,
-            designers,
-            senior_designers,
-            analysts,
-            senior_analysts,
-            directors,
-            vps,
-            executives,
-            generic,
+            attendees,
+            template,
         } => {
-            commands::log::run(
-                start,
-                end,
-                duration,
-                engineers,
-                senior_engineers,
-                staff_engineers,
-                principal_engineers,
-                pms,
-                senior_pms,
-                director_pms,
-                designers,
-                senior_designers,
-                analysts,
-                senior_analysts,
-                directors,
-                vps,
-                executives,
-                generic,
-            )?;
+            commands::estimate::run(duration, attendees, template)?;
+        }
+
+        Commands::Show { id } => {
+            commands::show::run(&id)?;
+        }
+
+        Commands::Edit(args) => {
+            commands::edit::run(args)?;
         }
 
+        Commands::Delete { id } => {
+            commands::delete::run(&id)?;
+        }
+
+        Commands::Trash { action } => match action {
+            TrashCommands::List => {
+                commands::trash::list()?;
+            }
+            TrashCommands::Restore { id } => {
+                commands::trash::restore(&id)?;
+            }
+        },
+
+        Commands::Template { action } => match action {
+            TemplateCommands::Add {
+                name,
+                attendees,
+                duration,
+                labels,
+            } => {
+                commands::template::add(&name, attendees, duration, labels)?;
+            }
+            TemplateCommands::List => {
+                commands::template::list()?;
+            }
+            TemplateCommands::Remove { name } => {
+                commands::template::remove(&name)?;
+            }
+        },
+
+        Commands::Series { action } => match action {
+            SeriesCommands::Add(args) => {
+                commands::series::add(args)?;
+            }
+            SeriesCommands::List => {
+                commands::series::list()?;
+            }
+            SeriesCommands::Show { name } => {
+                commands::series::show(&name)?;
+            }
+            SeriesCommands::Remove { name } => {
+                commands::series::remove(&name)?;
+            }
+            SeriesCommands::Materialize { name } => {
+                commands::series::materialize(name.as_deref())?;
+            }
+        },
+
+        Commands::Team { action } => match action {
+            TeamCommands::Add { name, department } => {
+                commands::team::add(&name, department)?;
+            }
+            TeamCommands::List => {
+                commands::team::list()?;
+            }
+            TeamCommands::Remove { name } => {
+                commands::team::remove(&name)?;
+            }
+        },
+
+        Commands::People { action } => match action {
+            PeopleCommands::Add {
+                name,
+                role,
+                rate,
+                currency,
+                team,
+            } => {
+                commands::people::add(&name, &role, rate, currency, team)?;
+            }
+            PeopleCommands::List => {
+                commands::people::list()?;
+            }
+            PeopleCommands::Remove { name } => {
+                commands::people::remove(&name)?;
+            }
+        },
+
         Commands::Config { action } => match action {
-            ConfigCommands::SetRate { role, rate } => {
-                commands::config::set_rate(&role, rate)?;
+            ConfigCommands::SetRate {
+                role,
+                rate,
+                currency,
+                from,
+            } => {
+                commands::config::set_rate(&role, rate, currency, from)?;
+            }
+            ConfigCommands::SetSalary {
+                role,
+                salary,
+                currency,
+                from,
+            } => {
+                commands::config::set_salary(&role, salary, currency, from)?;
             }
             ConfigCommands::ListRates => {
                 commands::config::list_rates()?;
             }
+            ConfigCommands::AddRole {
+                id,
+                rate,
+                currency,
+                name,
+                aliases,
+            } => {
+                commands::config::add_role(&id, name, rate, currency, aliases)?;
+            }
+            ConfigCommands::RemoveRole { role } => {
+                commands::config::remove_role(&role)?;
+            }
+            ConfigCommands::Set { setting } => match setting {
+                SetCommands::Overhead {
+                    percent,
+                    role,
+                    clear: _,
+                } => {
+                    commands::config::set_overhead(percent, role.as_deref())?;
+                }
+                SetCommands::ContextSwitch { minutes } => {
+                    commands::config::set_context_switch(minutes)?;
+                }
+                SetCommands::Rounding { policy } => {
+                    commands::config::set_rounding(policy)?;
+                }
+                SetCommands::WorkingHours { hours } => {
+                    commands::config::set_working_hours(hours)?;
+                }
+                SetCommands::Holidays { days } => {
+                    commands::config::set_holidays(days)?;
+                }
+                SetCommands::Pto { days } => {
+                    commands::config::set_pto(days)?;
+                }
+                SetCommands::Currency { currency } => {
+                    commands::config::set_currency(currency)?;
+                }
+                SetCommands::Locale { locale } => {
+                    commands::config::set_locale(locale)?;
+                }
+            },
+            ConfigCommands::ExchangeRate { action } => match action {
+                ExchangeRateCommands::Set {
+                    currency,
+                    usd_value,
+                } => {
+                    commands::config::set_exchange_rate(currency, usd_value)?;
+                }
+                ExchangeRateCommands::Remove { currency } => {
+                    commands::config::remove_exchange_rate(&currency)?;
+                }
+                ExchangeRateCommands::Import { file } => {
+                    commands::config::import_exchange_rates(&file)?;
+                }
+            },
             ConfigCommands::Reset => {
                 commands::config::reset()?;
             }
         },
 
-        Commands::History { limit } => {
-            commands::history::run(limit)?;
+        Commands::Storage { action } => match action {
+            StorageCommands::Status => {
+                commands::storage::status()?;
+            }
+            StorageCommands::Migrate => {
+                commands::storage::migrate()?;
+            }
+            StorageCommands::Use { backend } => {
+                commands::storage::use_backend(backend)?;
+            }
+        },
+
+        Commands::Doctor => {
+            commands::doctor::run()?;
         }
 
-        Commands::Report { week, month, all } => {
-            commands::report::run(week, month, all)?;
+        Commands::History(args) => {
+            commands::history::run(args)?;
+        }
+
+        Commands::Report {
+            week,
+            month,
+            all,
+            top,
+            by,
+        } => {
+            commands::report::run(week, month, all, top, by)?;
+        }
+
+        Commands::Recalculate {
+            since,
+            until,
+            dry_run,
+        } => {
+            commands::recalculate::run(since, until, dry_run)?;
         }
     }
 
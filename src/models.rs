@@ -1,90 +1,251 @@
-use chrono::{DateTime, Local};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Local, NaiveDate};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum Role {
-    Engineer,
-    SeniorEngineer,
-    StaffEngineer,
-    PrincipalEngineer,
-    ProductManager,
-    SeniorPm,
-    DirectorPm,
-    Designer,
-    SeniorDesigner,
-    Analyst,
-    SeniorAnalyst,
-    Director,
-    Vp,
-    Executive,
-    Generic,
-}
+use crate::money::{Currency, Locale, Money};
+use crate::storage::StorageBackend;
+
+/// Identifier of a role declared in `config.toml`, e.g. `senior_engineer`.
+///
+/// Serialized as the bare id string, which is also how the built-in roles
+/// were written to `meetings.json` before roles became configurable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Role(String);
 
 impl Role {
-    pub fn default_rate(&self) -> f64 {
-        match self {
-            Role::Engineer => 43.0,           // $86K/year (was $200K)
-            Role::SeniorEngineer => 66.0,     // $132K/year (was $260K)
-            Role::StaffEngineer => 83.0,      // $167K/year (was $320K)
-            Role::PrincipalEngineer => 103.0, // $207K/year (was $400K)
-            Role::ProductManager => 72.0,     // $150K/year (was $240K)
-            Role::SeniorPm => 88.0,           // $184K/year (was $300K)
-            Role::DirectorPm => 105.0,        // $218K/year (was $400K)
-            Role::Designer => 57.0,           // $113K/year (was $220K)
-            Role::SeniorDesigner => 60.0,     // $121K/year (was $280K)
-            Role::Analyst => 50.0,            // $98K/year (was $180K)
-            Role::SeniorAnalyst => 64.0,      // $127K/year (was $230K)
-            Role::Director => 105.0,          // $218K/year (was $400K)
-            Role::Vp => 106.0,                // $220K/year (was $600K)
-            Role::Executive => 139.0,         // $288K/year (was $800K)
-            Role::Generic => 43.0,            // $86K/year (was $200K)
-        }
-    }
-
-    pub fn all_roles() -> Vec<Role> {
-        vec![
-            Role::Engineer,
-            Role::SeniorEngineer,
-            Role::StaffEngineer,
-            Role::PrincipalEngineer,
-            Role::ProductManager,
-            Role::SeniorPm,
-            Role::DirectorPm,
-            Role::Designer,
-            Role::SeniorDesigner,
-            Role::Analyst,
-            Role::SeniorAnalyst,
-            Role::Director,
-            Role::Vp,
-            Role::Executive,
-            Role::Generic,
-        ]
+    pub fn new(id: &str) -> Self {
+        Self(Self::normalize(id))
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    /// Canonical form used for ids and alias lookups: lowercase, with spaces
+    /// and dashes turned into underscores.
+    pub fn normalize(s: &str) -> String {
+        s.trim().to_lowercase().replace([' ', '-'], "_")
     }
 }
 
 impl fmt::Display for Role {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = match self {
-            Role::Engineer => "Engineer",
-            Role::SeniorEngineer => "Senior Engineer",
-            Role::StaffEngineer => "Staff Engineer",
-            Role::PrincipalEngineer => "Principal Engineer",
-            Role::ProductManager => "Product Manager",
-            Role::SeniorPm => "Senior PM",
-            Role::DirectorPm => "Director of PM",
-            Role::Designer => "Designer",
-            Role::SeniorDesigner => "Senior Designer",
-            Role::Analyst => "Analyst",
-            Role::SeniorAnalyst => "Senior Analyst",
-            Role::Director => "Director",
-            Role::Vp => "VP",
-            Role::Executive => "Executive",
-            Role::Generic => "Generic",
-        };
-        write!(f, "{}", s)
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A role as declared in the `[[roles]]` section of `config.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoleDef {
+    pub id: Role,
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Hourly rate, in `currency`, until the first of `rate_changes`.
+    pub rate: Money,
+    #[serde(default, skip_serializing_if = "Currency::is_usd")]
+    pub currency: Currency,
+    /// Overrides `Config::overhead_percent` for this role.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overhead_percent: Option<f64>,
+    /// Later rates and the day each took effect, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rate_changes: Vec<RateChange>,
+}
+
+/// A role's rate from `from` onwards, until its next change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateChange {
+    pub from: NaiveDate,
+    pub rate: Money,
+    #[serde(default, skip_serializing_if = "Currency::is_usd")]
+    pub currency: Currency,
+}
+
+impl RoleDef {
+    pub fn new(id: &str, name: &str, aliases: &[&str], rate: Money) -> Self {
+        Self {
+            id: Role::new(id),
+            name: name.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            rate,
+            currency: Currency::usd(),
+            overhead_percent: None,
+            rate_changes: Vec::new(),
+        }
+    }
+
+    /// The rate in force on `date`, and the currency it is in.
+    pub fn rate_on(&self, date: NaiveDate) -> (Money, &Currency) {
+        self.rate_changes
+            .iter()
+            .rfind(|change| change.from <= date)
+            .map_or((self.rate, &self.currency), |change| {
+                (change.rate, &change.currency)
+            })
+    }
+
+    /// Makes `rate` the role's rate from `from` until its next change,
+    /// replacing any change already made that day.
+    pub fn set_rate_from(&mut self, from: NaiveDate, rate: Money, currency: Currency) {
+        self.rate_changes.retain(|change| change.from != from);
+        self.rate_changes.push(RateChange {
+            from,
+            rate,
+            currency,
+        });
+        self.rate_changes.sort_by_key(|change| change.from);
+    }
+
+    /// Every rate the role has had, with its currency.
+    fn all_rates(&self) -> impl Iterator<Item = (Money, &Currency)> {
+        std::iter::once((self.rate, &self.currency))
+            .chain(self.rate_changes.iter().map(|change| (change.rate, &change.currency)))
+    }
+
+    fn answers_to(&self, normalized: &str) -> bool {
+        self.id.id() == normalized
+            || Role::normalize(&self.name) == normalized
+            || self.aliases.iter().any(|a| Role::normalize(a) == normalized)
+    }
+}
+
+/// Someone in the `[[people]]` directory of `config.toml`, so meetings can
+/// be attended by name rather than only by role.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Person {
+    pub name: String,
+    pub role: Role,
+    /// Hourly rate, in `currency`, charged instead of the role's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<Money>,
+    #[serde(default, skip_serializing_if = "Currency::is_usd")]
+    pub currency: Currency,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+}
+
+impl Person {
+    /// Whether `name` refers to this person. Names are matched ignoring case
+    /// and surrounding whitespace.
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name.to_lowercase() == name.trim().to_lowercase()
+    }
+}
+
+/// Attendees who were only at part of a meeting: `count` of `role`, or the
+/// named person `name`, from `joined` until `left`. They are still counted
+/// in the meeting's `attendees`, and named people in its `people`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attendance {
+    pub role: Role,
+    pub count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub joined: DateTime<Local>,
+    pub left: DateTime<Local>,
+}
+
+/// A stretch of a meeting when the meter was paused, which isn't billed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pause {
+    pub paused: DateTime<Local>,
+    pub resumed: DateTime<Local>,
+}
+
+/// Some of a meeting's attendees and when they were there; see
+/// `Meeting::presence`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Presence<'a> {
+    pub role: &'a Role,
+    /// Set when this is one named person.
+    pub person: Option<&'a Participant>,
+    pub heads: u32,
+    pub intervals: Vec<(DateTime<Local>, DateTime<Local>)>,
+}
+
+impl Presence<'_> {
+    /// How long each of the heads was there.
+    pub fn duration(&self) -> Duration {
+        self.intervals
+            .iter()
+            .map(|(joined, left)| *left - *joined)
+            .fold(Duration::zero(), |total, part| total + part)
+    }
+}
+
+/// A team declared in the `[[teams]]` section of `config.toml`. People
+/// belong to a team, and meetings can be owned by one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Team {
+    /// Stored in `Meeting::normalize_label` form.
+    pub name: String,
+    /// Department the team rolls up into, in `normalize_label` form.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub department: Option<String>,
+}
+
+/// A named attendee of a meeting, with the role they attended as. They are
+/// also counted in the meeting's `attendees`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Participant {
+    pub name: String,
+    pub role: Role,
+    /// The team they were on at the time, so later moves don't rewrite
+    /// old reports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+}
+
+/// The roles shipped with meeting-meter, used until the config says otherwise.
+/// The comments give the annual salaries the rates were worked out from;
+/// `config set-salary` now does that conversion.
+pub fn default_roles() -> Vec<RoleDef> {
+    vec![
+        RoleDef::new("engineer", "Engineer", &["eng"], Money::dollars(43)), // $86K/year (was $200K)
+        RoleDef::new("senior_engineer", "Senior Engineer", &["senior_eng", "sr_engineer"], Money::dollars(66)), // $132K/year (was $260K)
+        RoleDef::new("staff_engineer", "Staff Engineer", &["staff_eng"], Money::dollars(83)), // $167K/year (was $320K)
+        RoleDef::new("principal_engineer", "Principal Engineer", &["principal_eng", "principal"], Money::dollars(103)), // $207K/year (was $400K)
+        RoleDef::new("product_manager", "Product Manager", &["pm"], Money::dollars(72)), // $150K/year (was $240K)
+        RoleDef::new("senior_pm", "Senior PM", &["senior_product_manager", "sr_pm"], Money::dollars(88)), // $184K/year (was $300K)
+        RoleDef::new("director_pm", "Director of PM", &["director_of_pm", "pm_director"], Money::dollars(105)), // $218K/year (was $400K)
+        RoleDef::new("designer", "Designer", &["design"], Money::dollars(57)), // $113K/year (was $220K)
+        RoleDef::new("senior_designer", "Senior Designer", &["sr_designer"], Money::dollars(60)), // $121K/year (was $280K)
+        RoleDef::new("analyst", "Analyst", &[], Money::dollars(50)), // $98K/year (was $180K)
+        RoleDef::new("senior_analyst", "Senior Analyst", &["sr_analyst"], Money::dollars(64)), // $127K/year (was $230K)
+        RoleDef::new("director", "Director", &["dir"], Money::dollars(105)), // $218K/year (was $400K)
+        RoleDef::new("vp", "VP", &["vice_president"], Money::dollars(106)), // $220K/year (was $600K)
+        RoleDef::new("executive", "Executive", &["exec", "c_level"], Money::dollars(139)), // $288K/year (was $800K)
+        RoleDef::new("generic", "Generic", &[], Money::dollars(43)), // $86K/year (was $200K)
+    ]
+}
+
+/// A named attendee mix declared in `templates.toml`, applied to `start` and
+/// `log` with `--template`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Template {
+    pub name: String,
+    /// Used by `log` when the invocation doesn't pin down the meeting's length.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_minutes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub attendees: BTreeMap<Role, u32>,
+}
+
+impl Template {
+    /// Copies the template's title, category and tags onto `meeting`.
+    pub fn apply_labels(&self, meeting: &mut Meeting) {
+        meeting.title = self.title.clone();
+        meeting.category = self.category.clone();
+        meeting.tags = self.tags.clone();
     }
 }
 
@@ -94,8 +255,36 @@ pub struct Meeting {
     pub start_time: DateTime<Local>,
     pub end_time: DateTime<Local>,
     pub attendees: HashMap<Role, u32>,
-    pub cost: f64,
+    pub cost: Money,
+    /// Currency `cost` is in: the display currency when it was priced.
+    #[serde(default, skip_serializing_if = "Currency::is_usd")]
+    pub currency: Currency,
     pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Free-form labels, stored in `normalize_label` form.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The kind of meeting (standup, planning, 1:1, retro, ...), stored in
+    /// `normalize_label` form.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Name of the recurring series this meeting is an occurrence of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series: Option<String>,
+    /// Attendees from the people directory, by name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub people: Vec<Participant>,
+    /// The team that owns the meeting, in `normalize_label` form. Attendees
+    /// who aren't named people count towards it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    /// Attendees who weren't there for the whole meeting.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attendance: Vec<Attendance>,
+    /// When the meter was paused, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pauses: Vec<Pause>,
 }
 
 impl Meeting {
@@ -103,7 +292,7 @@ impl Meeting {
         start_time: DateTime<Local>,
         end_time: DateTime<Local>,
         attendees: HashMap<Role, u32>,
-        cost: f64,
+        cost: Money,
     ) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -111,46 +300,699 @@ impl Meeting {
             end_time,
             attendees,
             cost,
+            currency: Currency::usd(),
             notes: None,
+            title: None,
+            tags: Vec::new(),
+            category: None,
+            series: None,
+            people: Vec::new(),
+            team: None,
+            attendance: Vec::new(),
+            pauses: Vec::new(),
+        }
+    }
+
+    /// Canonical form of a tag or category: lowercase, with runs of
+    /// whitespace turned into a single dash ("All Hands" -> "all-hands").
+    pub fn normalize_label(s: &str) -> String {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-")
+            .to_lowercase()
+    }
+
+    /// Turns `--tag` values, which may also be comma-separated, into a tag
+    /// list. Duplicates and empty entries are dropped.
+    pub fn parse_tags<S: AsRef<str>>(values: &[S]) -> Vec<String> {
+        let mut tags = Vec::new();
+        for value in values {
+            for tag in value.as_ref().split(',') {
+                let tag = Self::normalize_label(tag);
+                if !tag.is_empty() && !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
         }
+        tags
     }
 
+    /// Whole minutes billed, i.e. not paused.
     pub fn duration_minutes(&self) -> i64 {
-        self.end_time
-            .signed_duration_since(self.start_time)
-            .num_minutes()
+        self.billed_duration().num_minutes()
+    }
+
+    /// How long the meeting ran, pauses included.
+    pub fn wall_clock_duration(&self) -> Duration {
+        self.end_time - self.start_time
+    }
+
+    /// How long the meeting ran, less its pauses.
+    pub fn billed_duration(&self) -> Duration {
+        self.unpaused(self.start_time, self.end_time)
+            .iter()
+            .map(|(from, to)| *to - *from)
+            .fold(Duration::zero(), |total, part| total + part)
+    }
+
+    /// The parts of `from` to `to` when the meter wasn't paused.
+    fn unpaused(
+        &self,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+        let mut parts = Vec::new();
+        let mut cursor = from;
+        for pause in &self.pauses {
+            if pause.resumed <= cursor || pause.paused >= to {
+                continue;
+            }
+            if pause.paused > cursor {
+                parts.push((cursor, pause.paused));
+            }
+            cursor = pause.resumed;
+            if cursor >= to {
+                return parts;
+            }
+        }
+        parts.push((cursor, to));
+        parts
+    }
+
+    /// Who was at the meeting and when, leaving out the pauses. Attendees of
+    /// each role who aren't named come first, split by `attendance`, with
+    /// whoever it doesn't mention there throughout; then each named person,
+    /// there for their own intervals or else throughout.
+    pub fn presence(&self) -> Vec<Presence<'_>> {
+        let whole = self.unpaused(self.start_time, self.end_time);
+        let mut roles: Vec<(&Role, &u32)> = self.attendees.iter().collect();
+        roles.sort();
+
+        let mut presence = Vec::new();
+        for (role, count) in roles {
+            let named = self.people.iter().filter(|p| &p.role == role).count() as u32;
+            let mut rest = count.saturating_sub(named);
+            let mut parts = Vec::new();
+            for part in self
+                .attendance
+                .iter()
+                .filter(|a| a.name.is_none() && &a.role == role)
+            {
+                let heads = part.count.min(rest);
+                rest -= heads;
+                parts.push(Presence {
+                    role,
+                    person: None,
+                    heads,
+                    intervals: self.unpaused(part.joined, part.left),
+                });
+            }
+            presence.push(Presence {
+                role,
+                person: None,
+                heads: rest,
+                intervals: whole.clone(),
+            });
+            presence.extend(parts);
+        }
+        for participant in &self.people {
+            let mut parts = self
+                .attendance
+                .iter()
+                .filter(|a| a.name.as_deref() == Some(participant.name.as_str()))
+                .peekable();
+            let intervals = if parts.peek().is_some() {
+                parts.flat_map(|a| self.unpaused(a.joined, a.left)).collect()
+            } else {
+                whole.clone()
+            };
+            presence.push(Presence {
+                role: &participant.role,
+                person: Some(participant),
+                heads: 1,
+                intervals,
+            });
+        }
+        presence
+    }
+
+    /// Fails unless every `attendance` entry lies within the meeting and
+    /// matches its attendees.
+    pub fn check_attendance(&self) -> Result<()> {
+        let mut partial: HashMap<&Role, u32> = HashMap::new();
+        for part in &self.attendance {
+            let when = format!(
+                "{} - {}",
+                part.joined.format("%-I:%M %p"),
+                part.left.format("%-I:%M %p")
+            );
+            if part.joined < self.start_time || part.joined >= self.end_time || part.left > self.end_time {
+                return Err(anyhow!(
+                    "Attendance {} falls outside the meeting ({} - {}).",
+                    when,
+                    self.start_time.format("%-I:%M %p"),
+                    self.end_time.format("%-I:%M %p")
+                ));
+            }
+            if part.left <= part.joined {
+                return Err(anyhow!("Attendance {} must end after it starts.", when));
+            }
+            match &part.name {
+                Some(name) => {
+                    if !self.people.iter().any(|p| &p.name == name) {
+                        return Err(anyhow!("{} has attendance {} but isn't an attendee.", name, when));
+                    }
+                }
+                None => *partial.entry(&part.role).or_insert(0) += part.count,
+            }
+        }
+        for (role, heads) in partial {
+            let named = self.people.iter().filter(|p| &p.role == role).count() as u32;
+            let attending = self.attendees.get(role).copied().unwrap_or(0).saturating_sub(named);
+            if heads > attending {
+                return Err(anyhow!(
+                    "{} {} attendee{} have attendance times, but only {} attended.",
+                    heads,
+                    role,
+                    if heads == 1 { "" } else { "s" },
+                    attending
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a meeting's length is turned into billed time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+    /// Bill every second.
+    #[default]
+    Exact,
+    /// Round to the nearest whole minute.
+    NearestMinute,
+    /// Bill in 15-minute blocks, rounding up.
+    QuarterHour,
+}
+
+impl Rounding {
+    /// Minutes billed for a meeting lasting `duration`.
+    pub fn billed_minutes(self, duration: Duration) -> f64 {
+        let seconds = duration.num_seconds();
+        if seconds <= 0 {
+            return 0.0;
+        }
+        let minutes = seconds as f64 / 60.0;
+        match self {
+            Rounding::Exact => minutes,
+            Rounding::NearestMinute => minutes.round(),
+            Rounding::QuarterHour => (minutes / 15.0).ceil() * 15.0,
+        }
+    }
+}
+
+impl fmt::Display for Rounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rounding::Exact => write!(f, "exact"),
+            Rounding::NearestMinute => write!(f, "nearest-minute"),
+            Rounding::QuarterHour => write!(f, "quarter-hour"),
+        }
+    }
+}
+
+/// Weekdays in a year, used to turn holidays and PTO days into hours.
+const WORK_DAYS_PER_YEAR: f64 = 260.0;
+
+/// The working year used to convert between annual salaries and hourly rates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkYear {
+    /// Contracted hours in a year before time off, e.g. 2080 for 52 weeks
+    /// of 40 hours.
+    pub hours: f64,
+    /// Public holidays per year, in days.
+    pub holidays: u32,
+    /// Paid time off per year, in days.
+    pub pto_days: u32,
+}
+
+impl Default for WorkYear {
+    fn default() -> Self {
+        Self {
+            hours: 2080.0,
+            holidays: 10,
+            pto_days: 0,
+        }
+    }
+}
+
+impl WorkYear {
+    /// Hours actually worked: the contracted hours less holidays and PTO.
+    pub fn working_hours(&self) -> f64 {
+        let hours_per_day = self.hours / WORK_DAYS_PER_YEAR;
+        (self.hours - self.days_off() * hours_per_day).max(0.0)
+    }
+
+    /// Holidays and PTO days together.
+    pub fn days_off(&self) -> f64 {
+        f64::from(self.holidays) + f64::from(self.pto_days)
+    }
+
+    /// Fails if holidays and PTO leave no working hours in the year.
+    pub fn check(&self) -> Result<()> {
+        if self.working_hours() <= 0.0 {
+            return Err(anyhow!(
+                "Holidays and PTO ({} days) leave no working hours in the year.",
+                self.days_off()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Hourly rate that adds up to `salary` over the working hours.
+    pub fn hourly_rate(&self, salary: Money) -> Result<Money> {
+        self.check()?;
+        Ok(salary.mul_f64(1.0 / self.working_hours()))
+    }
+
+    /// What `rate` adds up to over a year's working hours.
+    pub fn annual_salary(&self, rate: Money) -> Money {
+        rate.mul_f64(self.working_hours())
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    pub rates: HashMap<Role, f64>,
+    /// Layout version of `config.toml`; see `config::CONFIG_SCHEMA_VERSION`.
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Benefits and other overhead, as a percentage added on top of every
+    /// hourly rate unless the role sets its own.
     #[serde(default)]
-    pub context_switch_multiplier: f64,
+    pub overhead_percent: f64,
+    /// Focus time each attendee loses getting into and back out of a
+    /// meeting, charged once per meeting.
     #[serde(default)]
-    pub include_benefits: bool,
+    pub context_switch_minutes: f64,
+    /// How a meeting's length is rounded before it is priced.
+    #[serde(default)]
+    pub rounding: Rounding,
+    #[serde(default)]
+    pub storage: StorageBackend,
+    /// Currency costs are calculated and shown in.
+    #[serde(default)]
+    pub currency: Currency,
+    /// How amounts are written, e.g. `en-US` or `de-DE`.
+    #[serde(default)]
+    pub locale: Locale,
+    /// Working time used by `config set-salary` and the annual figures in
+    /// `config list-rates`.
+    #[serde(default)]
+    pub work_year: WorkYear,
+    /// Value of one unit of each currency in US dollars, e.g. `EUR = 1.08`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub exchange_rates: BTreeMap<Currency, f64>,
+    #[serde(default = "default_roles")]
+    pub roles: Vec<RoleDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub teams: Vec<Team>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub people: Vec<Person>,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        let mut rates = HashMap::new();
-        for role in Role::all_roles() {
-            rates.insert(role, role.default_rate());
-        }
-
         Self {
-            rates,
-            context_switch_multiplier: 1.0,
-            include_benefits: false,
+            schema_version: crate::config::CONFIG_SCHEMA_VERSION,
+            overhead_percent: 0.0,
+            context_switch_minutes: 0.0,
+            rounding: Rounding::default(),
+            storage: StorageBackend::default(),
+            currency: Currency::default(),
+            locale: Locale::default(),
+            work_year: WorkYear::default(),
+            exchange_rates: BTreeMap::new(),
+            roles: default_roles(),
+            teams: Vec::new(),
+            people: Vec::new(),
         }
     }
 }
 
 impl Config {
-    pub fn get_rate(&self, role: &Role) -> f64 {
-        self.rates
-            .get(role)
-            .copied()
-            .unwrap_or_else(|| role.default_rate())
+    /// Today's hourly rate for `role` in the display currency.
+    pub fn get_rate(&self, role: &Role) -> Result<Money> {
+        self.rate_at(role, Local::now())
+    }
+
+    /// Hourly rate for `role` in force at `at`, in the display currency.
+    /// Fails for a role that is no longer in the config, rather than pricing
+    /// its attendees at nothing.
+    pub fn rate_at(&self, role: &Role, at: DateTime<Local>) -> Result<Money> {
+        let def = self.role(role).ok_or_else(|| {
+            anyhow!("Unknown role: {}. It is no longer in the config, so it can't be priced.", role)
+        })?;
+        let (rate, currency) = def.rate_on(at.date_naive());
+        // `check_exchange_rates` has made sure every role's rate converts.
+        Ok(self.to_display(rate, currency).unwrap_or(rate))
+    }
+
+    /// Overhead percentage applied to `role`: its own, or the global one.
+    pub fn overhead_percent(&self, role: &Role) -> f64 {
+        self.role(role)
+            .and_then(|def| def.overhead_percent)
+            .unwrap_or(self.overhead_percent)
+    }
+
+    /// Hourly rate including overhead, in force at `at`.
+    pub fn loaded_rate_at(&self, role: &Role, at: DateTime<Local>) -> Result<Money> {
+        Ok(self
+            .rate_at(role, at)?
+            .mul_f64(1.0 + self.overhead_percent(role) / 100.0))
+    }
+
+    /// Hourly rate `participant` is charged at `at`, in the display currency:
+    /// their own if the people directory gives them one, else their role's.
+    /// Personal rates aren't effective-dated, so the current one is used
+    /// whatever `at` is.
+    pub fn participant_rate_at(&self, participant: &Participant, at: DateTime<Local>) -> Result<Money> {
+        match self.person(&participant.name).and_then(|person| Some((person.rate?, &person.currency))) {
+            // `check_exchange_rates` has made sure every person's rate converts.
+            Some((rate, currency)) => Ok(self.to_display(rate, currency).unwrap_or(rate)),
+            None => self.rate_at(&participant.role, at),
+        }
+    }
+
+    /// `participant_rate_at` including their role's overhead.
+    pub fn loaded_participant_rate_at(&self, participant: &Participant, at: DateTime<Local>) -> Result<Money> {
+        Ok(self
+            .participant_rate_at(participant, at)?
+            .mul_f64(1.0 + self.overhead_percent(&participant.role) / 100.0))
+    }
+
+    /// Converts `amount` from one currency to another through
+    /// `exchange_rates`.
+    pub fn exchange(&self, amount: Money, from: &Currency, to: &Currency) -> Result<Money> {
+        if from == to {
+            return Ok(amount);
+        }
+        Ok(amount.mul_f64(self.usd_value(from)? / self.usd_value(to)?))
+    }
+
+    /// Converts `amount` from `from` into the display currency.
+    pub fn to_display(&self, amount: Money, from: &Currency) -> Result<Money> {
+        self.exchange(amount, from, &self.currency)
+    }
+
+    fn usd_value(&self, currency: &Currency) -> Result<f64> {
+        if currency.is_usd() {
+            return Ok(1.0);
+        }
+        self.exchange_rates.get(currency).copied().ok_or_else(|| {
+            anyhow!(
+                "No exchange rate for {}. Add one with `meeting-meter config exchange-rate set {} <usd-value>`.",
+                currency,
+                currency
+            )
+        })
+    }
+
+    /// Fails unless every rate each role has had can be converted into the
+    /// display currency.
+    pub fn check_exchange_rates(&self) -> Result<()> {
+        for def in &self.roles {
+            for (rate, currency) in def.all_rates() {
+                self.to_display(rate, currency)
+                    .map_err(|e| anyhow!("A {} rate is in {}: {}", def.name, currency, e))?;
+            }
+        }
+        for person in &self.people {
+            if let Some(rate) = person.rate {
+                self.to_display(rate, &person.currency)
+                    .map_err(|e| anyhow!("{}'s rate is in {}: {}", person.name, person.currency, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes `amount`, in `currency`, in the configured locale.
+    pub fn format_money(&self, amount: Money, currency: &Currency) -> String {
+        amount.format(currency, &self.locale)
+    }
+
+    pub fn role(&self, role: &Role) -> Option<&RoleDef> {
+        self.roles.iter().find(|def| &def.id == role)
+    }
+
+    /// Display name for `role`, falling back to its id for roles that have
+    /// since been removed from the config.
+    pub fn role_name(&self, role: &Role) -> String {
+        self.role(role)
+            .map_or_else(|| role.to_string(), |def| def.name.clone())
+    }
+
+    /// Resolves user input (an id, display name or alias) to a configured role.
+    pub fn resolve_role(&self, s: &str) -> Result<Role> {
+        let normalized = Role::normalize(s);
+        self.roles
+            .iter()
+            .find(|def| def.answers_to(&normalized))
+            .map(|def| def.id.clone())
+            .ok_or_else(|| {
+                let known: Vec<&str> = self.roles.iter().map(|def| def.id.id()).collect();
+                anyhow!("Unknown role: {}. Try one of: {}", s, known.join(", "))
+            })
+    }
+
+    pub fn team(&self, name: &str) -> Option<&Team> {
+        let name = Meeting::normalize_label(name);
+        self.teams.iter().find(|team| team.name == name)
+    }
+
+    /// Resolves a team name to its stored form, failing unless the team has
+    /// been declared.
+    pub fn resolve_team(&self, name: &str) -> Result<String> {
+        self.team(name).map(|team| team.name.clone()).ok_or_else(|| {
+            let name = Meeting::normalize_label(name);
+            if self.teams.is_empty() {
+                anyhow!("Unknown team: {}. Declare it with `meeting-meter team add {}`.", name, name)
+            } else {
+                let known: Vec<&str> = self.teams.iter().map(|t| t.name.as_str()).collect();
+                anyhow!("Unknown team: {}. Known teams: {}", name, known.join(", "))
+            }
+        })
+    }
+
+    pub fn person(&self, name: &str) -> Option<&Person> {
+        self.people.iter().find(|person| person.is_named(name))
+    }
+
+    /// Looks up someone in the people directory by name.
+    pub fn resolve_person(&self, name: &str) -> Result<&Person> {
+        self.person(name).ok_or_else(|| {
+            anyhow!(
+                "Unknown person: {}. Add them with `meeting-meter people add {} --role <role>`.",
+                name.trim(),
+                name.trim()
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_role_by_id_name_and_alias() {
+        let config = Config::default();
+        assert_eq!(config.resolve_role("senior_engineer").unwrap(), Role::new("senior_engineer"));
+        assert_eq!(config.resolve_role("Senior Engineer").unwrap(), Role::new("senior_engineer"));
+        assert_eq!(config.resolve_role("sr-engineer").unwrap(), Role::new("senior_engineer"));
+        assert_eq!(config.resolve_role("PM").unwrap(), Role::new("product_manager"));
+        assert!(config.resolve_role("wizard").is_err());
+    }
+
+    #[test]
+    fn test_custom_roles_from_toml() {
+        let config: Config = toml::from_str(
+            r#"
+            [[roles]]
+            id = "sre"
+            name = "Site Reliability Engineer"
+            aliases = ["ops"]
+            rate = 80.0
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.roles.len(), 1);
+        assert_eq!(config.resolve_role("ops").unwrap(), Role::new("sre"));
+        assert_eq!(config.get_rate(&Role::new("sre")).unwrap(), Money::dollars(80));
+        assert_eq!(config.role_name(&Role::new("sre")), "Site Reliability Engineer");
+        // A role that has been removed can't be priced, rather than costing nothing.
+        assert!(config.get_rate(&Role::new("engineer")).is_err());
+    }
+
+    #[test]
+    fn test_rates_convert_to_display_currency() {
+        let mut config: Config = toml::from_str(
+            r#"
+            currency = "GBP"
+
+            [exchange_rates]
+            EUR = 1.08
+            GBP = 1.35
+
+            [[roles]]
+            id = "engineer"
+            name = "Engineer"
+            rate = 54.0
+            currency = "EUR"
+
+            [[roles]]
+            id = "pm"
+            name = "PM"
+            rate = 67.5
+            "#,
+        )
+        .unwrap();
+
+        config.check_exchange_rates().unwrap();
+        // 54 EUR = 58.32 USD = 43.20 GBP, and 67.50 USD = 50 GBP.
+        assert_eq!(config.get_rate(&Role::new("engineer")).unwrap(), Money::from_f64(43.2));
+        assert_eq!(config.get_rate(&Role::new("pm")).unwrap(), Money::dollars(50));
+        assert_eq!(config.format_money(Money::dollars(50), &config.currency), "£50.00");
+
+        config.exchange_rates.remove(&"EUR".parse().unwrap());
+        assert!(config.check_exchange_rates().is_err());
+    }
+
+    #[test]
+    fn test_effective_dated_rates() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let mut def = RoleDef::new("engineer", "Engineer", &[], Money::dollars(43));
+        def.set_rate_from(date("2026-07-01"), Money::dollars(50), Currency::usd());
+        def.set_rate_from(date("2026-01-01"), Money::dollars(45), Currency::usd());
+        def.set_rate_from(date("2026-07-01"), Money::dollars(48), Currency::usd());
+
+        assert_eq!(def.rate_changes.len(), 2);
+        assert_eq!(def.rate_on(date("2025-12-31")).0, Money::dollars(43));
+        assert_eq!(def.rate_on(date("2026-01-01")).0, Money::dollars(45));
+        assert_eq!(def.rate_on(date("2026-06-30")).0, Money::dollars(45));
+        assert_eq!(def.rate_on(date("2027-01-01")).0, Money::dollars(48));
+    }
+
+    #[test]
+    fn test_salary_and_hourly_conversion() {
+        let year = WorkYear::default();
+        assert_eq!(year.working_hours(), 2000.0);
+        assert_eq!(year.hourly_rate(Money::dollars(150_000)).unwrap(), Money::dollars(75));
+        assert_eq!(year.annual_salary(Money::dollars(43)), Money::dollars(86_000));
+
+        let year = WorkYear {
+            hours: 1820.0,
+            holidays: 8,
+            pto_days: 25,
+        };
+        assert_eq!(year.working_hours(), 1589.0);
+        assert_eq!(year.hourly_rate(Money::dollars(95_340)).unwrap(), Money::dollars(60));
+
+        let config: Config = toml::from_str("[work_year]\npto_days = 15\n").unwrap();
+        assert_eq!(config.work_year.hours, 2080.0);
+        assert_eq!(config.work_year.working_hours(), 1880.0);
+
+        let year = WorkYear {
+            holidays: u32::MAX,
+            pto_days: 1,
+            ..Default::default()
+        };
+        assert_eq!(year.working_hours(), 0.0);
+        assert!(year.hourly_rate(Money::dollars(100_000)).is_err());
+    }
+
+    #[test]
+    fn test_meeting_attendees_keep_snake_case_keys() {
+        let json = r#"{"engineer": 3, "senior_pm": 1}"#;
+        let attendees: HashMap<Role, u32> = serde_json::from_str(json).unwrap();
+        assert_eq!(attendees.get(&Role::new("engineer")), Some(&3));
+        assert_eq!(attendees.get(&Role::new("senior_pm")), Some(&1));
+    }
+
+    #[test]
+    fn test_meeting_tags_are_normalized() {
+        let tags = Meeting::parse_tags(&["Q3 Launch, infra", "infra", " ", "1:1"]);
+        assert_eq!(tags, vec!["q3-launch", "infra", "1:1"]);
+        assert_eq!(Meeting::normalize_label("  All  Hands "), "all-hands");
+    }
+
+    #[test]
+    fn test_presence_splits_partial_attendance() {
+        let start = Local::now();
+        let at = |minutes| start + Duration::minutes(minutes);
+        let engineer = Role::new("engineer");
+        let mut meeting = Meeting::new(start, at(60), HashMap::from([(engineer, 3)]), Money::ZERO);
+        let partial = |count, joined, left| Attendance {
+            role: Role::new("engineer"),
+            count,
+            name: None,
+            joined: at(joined),
+            left: at(left),
+        };
+        meeting.attendance = vec![partial(2, 30, 60)];
+        meeting.check_attendance().unwrap();
+
+        let heads: Vec<(u32, Duration)> = meeting
+            .presence()
+            .iter()
+            .map(|presence| (presence.heads, presence.duration()))
+            .collect();
+        assert_eq!(heads, vec![(1, Duration::minutes(60)), (2, Duration::minutes(30))]);
+
+        meeting.attendance = vec![partial(2, 30, 60), partial(2, 0, 15)];
+        assert!(meeting.check_attendance().is_err());
+        meeting.attendance = vec![partial(1, 30, 75)];
+        assert!(meeting.check_attendance().is_err());
+        meeting.attendance = vec![partial(1, 30, 20)];
+        assert!(meeting.check_attendance().is_err());
+    }
+
+    #[test]
+    fn test_pauses_are_not_billed() {
+        let start = Local::now();
+        let at = |minutes| start + Duration::minutes(minutes);
+        let mut meeting = Meeting::new(start, at(60), HashMap::from([(Role::new("engineer"), 2)]), Money::ZERO);
+        meeting.attendance = vec![Attendance {
+            role: Role::new("engineer"),
+            count: 1,
+            name: None,
+            joined: at(30),
+            left: at(60),
+        }];
+        meeting.pauses = vec![
+            Pause { paused: at(10), resumed: at(20) },
+            Pause { paused: at(25), resumed: at(35) },
+        ];
+
+        assert_eq!(meeting.wall_clock_duration(), Duration::minutes(60));
+        assert_eq!(meeting.billed_duration(), Duration::minutes(40));
+        let durations: Vec<Duration> = meeting.presence().iter().map(Presence::duration).collect();
+        assert_eq!(durations, vec![Duration::minutes(40), Duration::minutes(25)]);
+    }
+
+    #[test]
+    fn test_meeting_without_labels_still_loads() {
+        let json = r#"{
+            "id": "a8e2b7c4",
+            "start_time": "2026-01-05T10:00:00-06:00",
+            "end_time": "2026-01-05T10:30:00-06:00",
+            "attendees": {"engineer": 2},
+            "cost": 43.0,
+            "notes": null
+        }"#;
+        let meeting: Meeting = serde_json::from_str(json).unwrap();
+        assert!(meeting.title.is_none() && meeting.tags.is_empty() && meeting.category.is_none());
     }
 }
@@ -0,0 +1,491 @@
+//! `money.rs`
+//
+// Exact currency amounts. `Money` is a fixed-point number of ten-thousandths
+// of a currency unit, so costs can be summed across any number of meetings
+// without the drift `f64` accumulates. Fractions of a cent are kept because
+// rates are charged by the second; they are only rounded away when an amount
+// is shown.
+//
+// `Money` doesn't know its currency; whoever holds one keeps the `Currency`
+// next to it (a role's rate, a meeting's cost) or it is in the configured
+// display currency. `Money::format` writes an amount the way a `Locale`
+// expects.
+//
+// Amounts are written to `meetings.json` and `config.toml` as plain numbers
+// (`138.5`), exactly as the old `f64` fields were, so existing files load
+// unchanged.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Fractional digits kept below the currency unit.
+const DECIMALS: u32 = 4;
+const SCALE: i64 = 10_i64.pow(DECIMALS);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    /// A whole number of currency units (dollars, euros, ...).
+    pub const fn dollars(dollars: i64) -> Self {
+        Self(dollars.saturating_mul(SCALE))
+    }
+
+    /// Converts a floating-point amount, rounding to the nearest
+    /// ten-thousandth of a currency unit.
+    pub fn from_f64(amount: f64) -> Self {
+        Self((amount * SCALE as f64).round() as i64)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+
+    /// Scales the amount by `factor` (hours, head count, a percentage),
+    /// rounding the result to the nearest ten-thousandth of a currency unit.
+    pub fn mul_f64(self, factor: f64) -> Self {
+        Self((self.0 as f64 * factor).round() as i64)
+    }
+
+    /// The amount divided evenly `count` ways, rounded to the nearest
+    /// ten-thousandth. Zero when `count` is zero.
+    pub fn div_round(self, count: usize) -> Self {
+        if count == 0 {
+            return Self::ZERO;
+        }
+        let count = count as i128;
+        let amount = self.0 as i128;
+        let half = if amount < 0 { -count / 2 } else { count / 2 };
+        Self(((amount + half) / count) as i64)
+    }
+
+    /// Splits the amount in proportion to `weights`.
+    ///
+    /// The shares always add up to exactly `self`: whatever rounding leaves
+    /// over goes to the largest weight. With no positive weight, every share
+    /// is zero.
+    pub fn split(self, weights: &[f64]) -> Vec<Money> {
+        let total: f64 = weights.iter().filter(|w| **w > 0.0).sum();
+        if total <= 0.0 {
+            return vec![Self::ZERO; weights.len()];
+        }
+
+        let mut shares: Vec<Money> = weights
+            .iter()
+            .map(|w| if *w > 0.0 { self.mul_f64(w / total) } else { Self::ZERO })
+            .collect();
+
+        let remainder = self - shares.iter().copied().sum();
+        if let Some(largest) = (0..weights.len()).max_by(|a, b| weights[*a].total_cmp(&weights[*b])) {
+            shares[largest] += remainder;
+        }
+        shares
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Writes the amount in `currency` as `locale` expects, rounded to the
+    /// currency's smallest unit: `$1,234.50` in `en-US`, `1.234,50 €` in
+    /// `de-DE`.
+    pub fn format(self, currency: &Currency, locale: &Locale) -> String {
+        let digits = currency.minor_digits();
+        let per_unit = SCALE / 10_i64.pow(digits);
+        let per_unit = per_unit as u64;
+        let magnitude = self.0.unsigned_abs();
+        let minor = magnitude / per_unit + u64::from(magnitude % per_unit * 2 >= per_unit);
+        let (whole, fraction) = (minor / 10_u64.pow(digits), minor % 10_u64.pow(digits));
+
+        let style = locale.style();
+        let mut number = group_thousands(whole, style.group);
+        if digits > 0 {
+            number.push(style.decimal);
+            number.push_str(&format!("{:0width$}", fraction, width = digits as usize));
+        }
+
+        let sign = if self.0 < 0 && minor > 0 { "-" } else { "" };
+        match (currency.symbol(), style.symbol_first) {
+            (Some(symbol), true) => format!("{}{}{}", sign, symbol, number),
+            (Some(symbol), false) => format!("{}{} {}", sign, number, symbol),
+            (None, true) => format!("{}{} {}", sign, currency, number),
+            (None, false) => format!("{}{} {}", sign, number, currency),
+        }
+    }
+}
+
+fn group_thousands(whole: u64, separator: char) -> String {
+    let digits = whole.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+// Arithmetic saturates rather than overflowing: `from_f64` and `mul_f64`
+// already clamp huge amounts to the largest representable one.
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money(self.0.saturating_add(other.0))
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money(self.0.saturating_sub(other.0))
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(self.0.saturating_neg())
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+/// Parses a decimal amount such as `43`, `43.5` or `$1,250.00` exactly.
+impl FromStr for Money {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid amount: '{}'. Use a number like 43 or 43.50", s);
+
+        let trimmed = s.trim();
+        let (negative, rest) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let digits: String = rest.strip_prefix('$').unwrap_or(rest).replace(',', "");
+        let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+
+        if whole.is_empty() && fraction.is_empty()
+            || fraction.len() > DECIMALS as usize
+            || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+        let fraction: i64 = format!("{:0<width$}", fraction, width = DECIMALS as usize)
+            .parse()
+            .map_err(|_| invalid())?;
+        let units = whole
+            .checked_mul(SCALE)
+            .and_then(|units| units.checked_add(fraction))
+            .ok_or_else(invalid)?;
+
+        Ok(Money(if negative { -units } else { units }))
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+/// Accepts any JSON or TOML number, integer or float.
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Money::from_f64)
+    }
+}
+
+/// An ISO 4217 currency code such as `USD`, `EUR` or `GBP`.
+///
+/// Rates were always US dollars before currencies became configurable, so
+/// anything without a currency is in `USD`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency(String);
+
+impl Currency {
+    pub fn usd() -> Self {
+        Self("USD".to_string())
+    }
+
+    pub fn is_usd(&self) -> bool {
+        self.0 == "USD"
+    }
+
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+
+    /// Symbol written instead of the code, for currencies that have a
+    /// widely recognised one.
+    fn symbol(&self) -> Option<&'static str> {
+        match self.0.as_str() {
+            "USD" => Some("$"),
+            "EUR" => Some("€"),
+            "GBP" => Some("£"),
+            "JPY" => Some("¥"),
+            "INR" => Some("₹"),
+            _ => None,
+        }
+    }
+
+    /// Digits after the decimal point in the currency's smallest unit.
+    fn minor_digits(&self) -> u32 {
+        match self.0.as_str() {
+            "JPY" | "KRW" | "ISK" => 0,
+            _ => 2,
+        }
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self::usd()
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let code = s.trim().to_uppercase();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(anyhow!(
+                "Invalid currency: '{}'. Use a three-letter code like USD, EUR or GBP",
+                s
+            ));
+        }
+        Ok(Self(code))
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> String {
+        currency.0
+    }
+}
+
+/// A BCP 47 language tag such as `en-US` or `de-DE`, deciding how amounts are
+/// written. Only the language part matters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Locale(String);
+
+/// Separators and symbol placement for one family of locales.
+struct NumberStyle {
+    group: char,
+    decimal: char,
+    symbol_first: bool,
+}
+
+impl Locale {
+    fn style(&self) -> NumberStyle {
+        let language = self.0.split(['-', '_']).next().unwrap_or_default();
+        match language {
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" => NumberStyle {
+                group: '.',
+                decimal: ',',
+                symbol_first: false,
+            },
+            "fr" | "sv" | "nb" | "no" | "fi" | "pl" | "cs" | "ru" => NumberStyle {
+                group: ' ',
+                decimal: ',',
+                symbol_first: false,
+            },
+            _ => NumberStyle {
+                group: ',',
+                decimal: '.',
+                symbol_first: true,
+            },
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self("en-US".to_string())
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.trim().split(['-', '_']).collect();
+        let valid = (2..=3).contains(&parts[0].len())
+            && parts[0].chars().all(|c| c.is_ascii_alphabetic())
+            && parts[1..]
+                .iter()
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()));
+        if !valid {
+            return Err(anyhow!("Invalid locale: '{}'. Use a tag like en-US, en-GB or de-DE", s));
+        }
+
+        // Canonical form: `fr_fr` becomes `fr-FR`.
+        let mut tag = parts[0].to_lowercase();
+        for part in &parts[1..] {
+            tag.push('-');
+            tag.push_str(&part.to_uppercase());
+        }
+        Ok(Self(tag))
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Locale> for String {
+    fn from(locale: Locale) -> String {
+        locale.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sums_do_not_drift() {
+        // 0.1 + 0.2 != 0.3 in f64; a thousand of them drift further.
+        let dime = Money::from_f64(0.1);
+        let total: Money = std::iter::repeat_n(dime, 1000).sum();
+        assert_eq!(total, Money::dollars(100));
+        assert_eq!(Money::from_f64(0.1) + Money::from_f64(0.2), Money::from_f64(0.3));
+    }
+
+    #[test]
+    fn test_huge_amounts_saturate() {
+        let huge = Money::from_f64(1e30);
+        assert_eq!(huge + huge, huge);
+        assert_eq!(-huge - huge, Money(i64::MIN));
+        assert_eq!(-Money(i64::MIN), huge);
+
+        let mut total = huge;
+        total += Money::dollars(1);
+        assert_eq!(total, huge);
+        assert_eq!(
+            Money(i64::MIN).format(&Currency::usd(), &Locale::default()),
+            "-$922,337,203,685,477.58"
+        );
+    }
+
+    #[test]
+    fn test_format_rounds_to_cents() {
+        let (usd, en) = (Currency::usd(), Locale::default());
+        assert_eq!(Money::from_f64(138.5).format(&usd, &en), "$138.50");
+        assert_eq!(Money::from_f64(1.43333).format(&usd, &en), "$1.43");
+        assert_eq!(Money::from_f64(0.005).format(&usd, &en), "$0.01");
+        assert_eq!(Money::from_f64(-3.25).format(&usd, &en), "-$3.25");
+        assert_eq!(Money::from_f64(-0.001).format(&usd, &en), "$0.00");
+        assert_eq!(Money::from_f64(1234567.891).format(&usd, &en), "$1,234,567.89");
+    }
+
+    #[test]
+    fn test_format_by_locale_and_currency() {
+        let amount = Money::from_f64(1234.5);
+        let eur: Currency = "eur".parse().unwrap();
+        let gbp: Currency = "GBP".parse().unwrap();
+        let chf: Currency = "CHF".parse().unwrap();
+        let jpy: Currency = "JPY".parse().unwrap();
+
+        assert_eq!(amount.format(&eur, &"de-DE".parse().unwrap()), "1.234,50 €");
+        assert_eq!(amount.format(&eur, &"fr_fr".parse().unwrap()), "1 234,50 €");
+        assert_eq!(amount.format(&gbp, &"en-GB".parse().unwrap()), "£1,234.50");
+        assert_eq!(amount.format(&chf, &Locale::default()), "CHF 1,234.50");
+        assert_eq!(amount.format(&jpy, &Locale::default()), "¥1,235");
+        assert_eq!("fr_fr".parse::<Locale>().unwrap().to_string(), "fr-FR");
+        assert!("dollars".parse::<Currency>().is_err());
+        assert!("e".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("43".parse::<Money>().unwrap(), Money::dollars(43));
+        assert_eq!("43.5".parse::<Money>().unwrap(), Money::from_f64(43.5));
+        assert_eq!("$1,250.00".parse::<Money>().unwrap(), Money::dollars(1250));
+        assert_eq!(".25".parse::<Money>().unwrap(), Money::from_f64(0.25));
+        assert!("".parse::<Money>().is_err());
+        assert!("12.345678".parse::<Money>().is_err());
+        assert!("abc".parse::<Money>().is_err());
+    }
+
+    #[test]
+    fn test_split_adds_up_exactly() {
+        let total = Money::dollars(100);
+        let shares = total.split(&[1.0, 1.0, 1.0]);
+        assert_eq!(shares.iter().sum::<Money>(), total);
+        assert_eq!(shares[1], Money::from_f64(33.3333));
+
+        assert_eq!(total.split(&[129.0, 105.0])[1], Money::from_f64(44.8718));
+        assert_eq!(total.split(&[0.0, 0.0]), vec![Money::ZERO; 2]);
+        assert_eq!(Money::dollars(10).div_round(3), Money::from_f64(3.3333));
+        assert_eq!(Money::dollars(10).div_round(0), Money::ZERO);
+    }
+
+    #[test]
+    fn test_serializes_as_plain_numbers() {
+        assert_eq!(serde_json::to_string(&Money::from_f64(138.5)).unwrap(), "138.5");
+        assert_eq!(serde_json::from_str::<Money>("43").unwrap(), Money::dollars(43));
+        assert_eq!(serde_json::from_str::<Money>("301.5").unwrap(), Money::from_f64(301.5));
+    }
+}
@@ -0,0 +1,572 @@
+//! `reports.rs`
+//
+// Aggregation logic shared by the `report` command. Everything in here is
+// pure: it takes a slice of meetings plus the configured rates and produces
+// totals, leaving all printing to `commands::report`.
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, Weekday};
+use std::collections::HashMap;
+
+use crate::models::{Config, Meeting, Participant, Role};
+use crate::money::Money;
+use crate::time_parser;
+
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Key that `report --by` breaks meetings down by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    Category,
+    Tag,
+    Title,
+    Team,
+}
+
+impl GroupBy {
+    /// Section heading used in the report.
+    pub fn heading(self) -> &'static str {
+        match self {
+            GroupBy::Category => "By Category",
+            GroupBy::Tag => "By Tag",
+            GroupBy::Title => "By Title",
+            GroupBy::Team => "By Team",
+        }
+    }
+
+    /// The keys `meeting` is counted under. Empty if it has none.
+    fn keys(self, meeting: &Meeting) -> Vec<String> {
+        match self {
+            GroupBy::Category => meeting.category.iter().cloned().collect(),
+            GroupBy::Tag => meeting.tags.clone(),
+            GroupBy::Title => meeting.title.iter().cloned().collect(),
+            // Only the owning team; `by_team` splits meetings across teams.
+            GroupBy::Team => meeting.team.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Totals for one bucket of meetings (a weekday, a role, ...).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Totals {
+    pub meetings: usize,
+    pub minutes: i64,
+    pub cost: Money,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleTotals {
+    pub role: Role,
+    /// Sum of the role's head count across all meetings.
+    pub attendees: u32,
+    pub person_hours: f64,
+    pub cost: Money,
+}
+
+/// Time and cost of one person from the people directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonTotals {
+    pub name: String,
+    pub meetings: usize,
+    pub hours: f64,
+    pub cost: Money,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportSummary {
+    pub meeting_count: usize,
+    pub total_minutes: i64,
+    pub person_hours: f64,
+    pub total_cost: Money,
+    /// Sorted by cost, most expensive role first.
+    pub by_role: Vec<RoleTotals>,
+    /// Named attendees, most hours in meetings first.
+    pub by_person: Vec<PersonTotals>,
+    /// Indexed Monday = 0 .. Sunday = 6, in local time.
+    pub by_weekday: [Totals; 7],
+    /// The most expensive meetings, most expensive first.
+    pub top_meetings: Vec<Meeting>,
+}
+
+impl ReportSummary {
+    pub fn average_cost(&self) -> Money {
+        self.total_cost.div_round(self.meeting_count)
+    }
+
+    pub fn average_minutes(&self) -> i64 {
+        if self.meeting_count == 0 {
+            0
+        } else {
+            self.total_minutes / self.meeting_count as i64
+        }
+    }
+}
+
+/// Restates every meeting's cost in the display currency, so meetings priced
+/// before the display currency changed can be added up with newer ones.
+pub fn in_display_currency(mut meetings: Vec<Meeting>, config: &Config) -> Result<Vec<Meeting>> {
+    for meeting in &mut meetings {
+        meeting.cost = config.to_display(meeting.cost, &meeting.currency)?;
+        meeting.currency = config.currency.clone();
+    }
+    Ok(meetings)
+}
+
+/// Aggregates `meetings` into a report, keeping the `top_n` most expensive.
+/// Costs are added up as they are, so convert them with
+/// `in_display_currency` first.
+///
+/// Stored meeting costs are authoritative. Each meeting's cost is split
+/// across its attendees in proportion to their loaded rates, using the
+/// rates in force when the meeting started, and the time each was there, so
+/// the per-role figures always add up to the total. Named people get their
+/// own share, which also counts towards their role.
+pub fn summarize(meetings: &[Meeting], config: &Config, top_n: usize) -> Result<ReportSummary> {
+    let mut by_role: HashMap<Role, RoleTotals> = HashMap::new();
+    let mut by_person: HashMap<String, PersonTotals> = HashMap::new();
+    let mut by_weekday = [Totals::default(); 7];
+    let mut total_minutes = 0;
+    let mut person_hours = 0.0;
+    let mut total_cost = Money::ZERO;
+
+    for meeting in meetings {
+        let minutes = meeting.duration_minutes().max(0);
+        total_minutes += minutes;
+        total_cost += meeting.cost;
+
+        let day = &mut by_weekday[meeting.start_time.weekday().num_days_from_monday() as usize];
+        day.meetings += 1;
+        day.minutes += minutes;
+        day.cost += meeting.cost;
+
+        for share in cost_shares(meeting, config)? {
+            let hours = share.heads as f64 * share.hours;
+            let entry = role_totals(&mut by_role, share.role);
+            entry.attendees += share.heads;
+            entry.person_hours += hours;
+            person_hours += hours;
+            entry.cost += share.cost;
+
+            if let Some(participant) = share.person {
+                let person = by_person
+                    .entry(participant.name.clone())
+                    .or_insert_with(|| PersonTotals {
+                        name: participant.name.clone(),
+                        meetings: 0,
+                        hours: 0.0,
+                        cost: Money::ZERO,
+                    });
+                person.meetings += 1;
+                person.hours += share.hours;
+                person.cost += share.cost;
+            }
+        }
+    }
+
+    let mut by_role: Vec<RoleTotals> = by_role.into_values().collect();
+    by_role.sort_by(|a, b| b.cost.cmp(&a.cost).then_with(|| a.role.cmp(&b.role)));
+
+    let mut by_person: Vec<PersonTotals> = by_person.into_values().collect();
+    by_person.sort_by(|a, b| {
+        b.hours
+            .total_cmp(&a.hours)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let mut top_meetings = meetings.to_vec();
+    top_meetings.sort_by_key(|m| std::cmp::Reverse(m.cost));
+    top_meetings.truncate(top_n);
+
+    Ok(ReportSummary {
+        meeting_count: meetings.len(),
+        total_minutes,
+        person_hours,
+        total_cost,
+        by_role,
+        by_person,
+        by_weekday,
+        top_meetings,
+    })
+}
+
+/// The part of a meeting's cost borne by some of its attendees.
+struct Share<'a> {
+    role: &'a Role,
+    heads: u32,
+    /// The named person the share belongs to, if it is one person's.
+    person: Option<&'a Participant>,
+    /// How long each of the heads was there.
+    hours: f64,
+    cost: Money,
+}
+
+/// Splits `meeting.cost` across its attendees in proportion to their loaded
+/// rates when it started and how long they were there: one share for each
+/// part of `Meeting::presence`, so one per role for the attendees who aren't
+/// named, more if some of them came late or left early, then one per named
+/// person. The shares add up to the cost exactly.
+fn cost_shares<'a>(meeting: &'a Meeting, config: &Config) -> Result<Vec<Share<'a>>> {
+    let mut shares: Vec<Share<'a>> = meeting
+        .presence()
+        .into_iter()
+        .map(|presence| Share {
+            role: presence.role,
+            heads: presence.heads,
+            person: presence.person,
+            hours: presence.duration().num_seconds().max(0) as f64 / 3600.0,
+            cost: Money::ZERO,
+        })
+        .collect();
+
+    let weights = shares
+        .iter()
+        .map(|share| {
+            let rate = match share.person {
+                Some(participant) => config.loaded_participant_rate_at(participant, meeting.start_time),
+                None => config.loaded_rate_at(share.role, meeting.start_time),
+            };
+            Ok(rate?.to_f64() * share.heads as f64 * share.hours)
+        })
+        .collect::<Result<Vec<f64>>>()
+        .with_context(|| {
+            format!("Can't split the cost of the meeting on {}", meeting.start_time.format("%Y-%m-%d %H:%M"))
+        })?;
+    for (share, cost) in shares.iter_mut().zip(meeting.cost.split(&weights)) {
+        share.cost = cost;
+    }
+    Ok(shares)
+}
+
+fn role_totals<'a>(by_role: &'a mut HashMap<Role, RoleTotals>, role: &Role) -> &'a mut RoleTotals {
+    by_role.entry(role.clone()).or_insert_with(|| RoleTotals {
+        role: role.clone(),
+        attendees: 0,
+        person_hours: 0.0,
+        cost: Money::ZERO,
+    })
+}
+
+/// Totals for one value of a `GroupBy` key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupTotals {
+    /// `None` collects the meetings that have no value for the key.
+    pub key: Option<String>,
+    pub totals: Totals,
+}
+
+impl GroupTotals {
+    pub fn average_cost(&self) -> Money {
+        self.totals.cost.div_round(self.totals.meetings)
+    }
+}
+
+/// Breaks `meetings` down by `by`, most expensive group first.
+///
+/// A meeting with several tags counts in full under each of them, so tag
+/// totals can add up to more than the report total.
+pub fn group(meetings: &[Meeting], by: GroupBy) -> Vec<GroupTotals> {
+    let mut groups: HashMap<Option<String>, Totals> = HashMap::new();
+
+    for meeting in meetings {
+        let mut keys: Vec<Option<String>> = by.keys(meeting).into_iter().map(Some).collect();
+        if keys.is_empty() {
+            keys.push(None);
+        }
+        for key in keys {
+            let totals = groups.entry(key).or_default();
+            totals.meetings += 1;
+            totals.minutes += meeting.duration_minutes().max(0);
+            totals.cost += meeting.cost;
+        }
+    }
+
+    let mut groups: Vec<GroupTotals> = groups
+        .into_iter()
+        .map(|(key, totals)| GroupTotals { key, totals })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.totals
+            .cost
+            .cmp(&a.totals.cost)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    groups
+}
+
+/// A department's share of meeting costs, with the teams that roll up into it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepartmentTotals {
+    /// `None` collects teams without a department, and attendees without a
+    /// team.
+    pub department: Option<String>,
+    pub totals: Totals,
+    /// Most expensive team first; a `None` key is attendees without a team.
+    pub teams: Vec<GroupTotals>,
+}
+
+/// Rolls meeting costs up by team and then department, most expensive
+/// department first.
+///
+/// Each meeting's cost is split across its attendees as in `summarize`.
+/// Named people count towards the team they were on at the time, and
+/// everyone else towards the team that owns the meeting, so a cross-team
+/// meeting is shared in proportion to what each team brought to it. A
+/// meeting counts once for each team and department that took part.
+pub fn by_team(meetings: &[Meeting], config: &Config) -> Result<Vec<DepartmentTotals>> {
+    let mut teams: HashMap<Option<String>, Totals> = HashMap::new();
+    let mut departments: HashMap<Option<String>, Totals> = HashMap::new();
+    let department_of = |team: &Option<String>| {
+        team.as_deref()
+            .and_then(|name| config.team(name))
+            .and_then(|team| team.department.clone())
+    };
+
+    for meeting in meetings {
+        let minutes = meeting.duration_minutes().max(0);
+        let mut meeting_teams: HashMap<Option<String>, Money> = HashMap::new();
+        for share in cost_shares(meeting, config)? {
+            if share.heads == 0 {
+                continue;
+            }
+            let team = share
+                .person
+                .and_then(|participant| participant.team.clone())
+                .or_else(|| meeting.team.clone());
+            *meeting_teams.entry(team).or_insert(Money::ZERO) += share.cost;
+        }
+
+        let mut meeting_departments: HashMap<Option<String>, Money> = HashMap::new();
+        for (team, cost) in meeting_teams {
+            *meeting_departments.entry(department_of(&team)).or_insert(Money::ZERO) += cost;
+            add_share(teams.entry(team).or_default(), minutes, cost);
+        }
+        for (department, cost) in meeting_departments {
+            add_share(departments.entry(department).or_default(), minutes, cost);
+        }
+    }
+
+    let mut rollup: Vec<DepartmentTotals> = departments
+        .into_iter()
+        .map(|(department, totals)| DepartmentTotals {
+            department,
+            totals,
+            teams: Vec::new(),
+        })
+        .collect();
+    for (team, totals) in teams {
+        let department = department_of(&team);
+        if let Some(entry) = rollup.iter_mut().find(|d| d.department == department) {
+            entry.teams.push(GroupTotals { key: team, totals });
+        }
+    }
+    for department in &mut rollup {
+        department.teams.sort_by(|a, b| {
+            b.totals
+                .cost
+                .cmp(&a.totals.cost)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+    }
+    rollup.sort_by(|a, b| {
+        b.totals
+            .cost
+            .cmp(&a.totals.cost)
+            .then_with(|| a.department.cmp(&b.department))
+    });
+    Ok(rollup)
+}
+
+fn add_share(totals: &mut Totals, minutes: i64, cost: Money) {
+    totals.meetings += 1;
+    totals.minutes += minutes;
+    totals.cost += cost;
+}
+
+/// The Monday-to-Monday window containing `today`.
+pub fn week_range(today: NaiveDate) -> Result<(DateTime<Local>, DateTime<Local>)> {
+    let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let next_monday = monday + Days::new(7);
+    Ok((
+        time_parser::start_of_day(monday)?,
+        time_parser::start_of_day(next_monday)?,
+    ))
+}
+
+/// The calendar month containing `today`.
+pub fn month_range(today: NaiveDate) -> Result<(DateTime<Local>, DateTime<Local>)> {
+    let first = today
+        .with_day(1)
+        .ok_or_else(|| anyhow!("Invalid date: {}", today))?;
+    let next_first = first
+        .checked_add_months(Months::new(1))
+        .ok_or_else(|| anyhow!("Date out of range: {}", first))?;
+    Ok((
+        time_parser::start_of_day(first)?,
+        time_parser::start_of_day(next_first)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Person, Team};
+    use crate::money::Currency;
+    use chrono::{Duration, TimeZone};
+
+    fn meeting(day: u32, minutes: i64, attendees: &[(Role, u32)], cost: i64) -> Meeting {
+        let start = Local.with_ymd_and_hms(2026, 1, day, 10, 0, 0).unwrap();
+        Meeting::new(
+            start,
+            start + Duration::minutes(minutes),
+            attendees.iter().cloned().collect(),
+            Money::dollars(cost),
+        )
+    }
+
+    #[test]
+    fn test_summarize_totals() {
+        let config = Config::default();
+        // 2026-01-19 is a Monday, 2026-01-21 a Wednesday.
+        let meetings = vec![
+            meeting(19, 60, &[(Role::new("engineer"), 3), (Role::new("director"), 1)], 234),
+            meeting(21, 30, &[(Role::new("engineer"), 2)], 43),
+        ];
+
+        let summary = summarize(&meetings, &config, 1).unwrap();
+        assert_eq!(summary.meeting_count, 2);
+        assert_eq!(summary.total_minutes, 90);
+        assert_eq!(summary.person_hours, 5.0);
+        assert_eq!(summary.total_cost, Money::dollars(277));
+        assert_eq!(summary.average_cost(), Money::from_f64(138.5));
+        assert_eq!(summary.by_weekday[0].meetings, 1);
+        assert_eq!(summary.by_weekday[2].cost, Money::dollars(43));
+        assert_eq!(summary.top_meetings.len(), 1);
+        assert_eq!(summary.top_meetings[0].cost, Money::dollars(234));
+
+        // 3 engineers @ $43 = $129 and 1 director @ $105 in the first meeting.
+        let engineer = summary.by_role.iter().find(|r| r.role == Role::new("engineer")).unwrap();
+        assert_eq!(engineer.attendees, 5);
+        assert_eq!(engineer.cost, Money::dollars(172));
+        let role_total: Money = summary.by_role.iter().map(|r| r.cost).sum();
+        assert_eq!(role_total, summary.total_cost);
+    }
+
+    #[test]
+    fn test_summarize_people() {
+        let mut config = Config::default();
+        config.people.push(Person {
+            name: "Priya".to_string(),
+            role: Role::new("engineer"),
+            rate: Some(Money::dollars(86)),
+            currency: Currency::usd(),
+            team: None,
+        });
+        let mut standup = meeting(19, 60, &[(Role::new("engineer"), 2)], 129);
+        standup.people = vec![Participant {
+            name: "Priya".to_string(),
+            role: Role::new("engineer"),
+            team: None,
+        }];
+        let anonymous = meeting(20, 60, &[(Role::new("engineer"), 1)], 43);
+
+        let summary = summarize(&[standup, anonymous], &config, 0).unwrap();
+        // Priya's $86 rate is twice the other engineer's $43.
+        assert_eq!(summary.by_person.len(), 1);
+        let priya = &summary.by_person[0];
+        assert_eq!((priya.meetings, priya.hours, priya.cost), (1, 1.0, Money::dollars(86)));
+
+        let engineer = &summary.by_role[0];
+        assert_eq!(engineer.attendees, 3);
+        assert_eq!(engineer.person_hours, 3.0);
+        assert_eq!(engineer.cost, Money::dollars(172));
+    }
+
+    #[test]
+    fn test_group_by_category_and_tag() {
+        let mut standup = meeting(19, 15, &[(Role::new("engineer"), 4)], 43);
+        standup.category = Some("standup".to_string());
+        standup.tags = vec!["infra".to_string(), "q3-launch".to_string()];
+        let mut planning = meeting(20, 60, &[(Role::new("engineer"), 4)], 172);
+        planning.category = Some("planning".to_string());
+        planning.tags = vec!["q3-launch".to_string()];
+        let untagged = meeting(21, 30, &[(Role::new("engineer"), 2)], 43);
+        let meetings = vec![standup.clone(), standup, planning, untagged];
+
+        let by_category = group(&meetings, GroupBy::Category);
+        let keys: Vec<Option<&str>> = by_category.iter().map(|g| g.key.as_deref()).collect();
+        assert_eq!(keys, vec![Some("planning"), Some("standup"), None]);
+        assert_eq!(by_category[1].totals.meetings, 2);
+        assert_eq!(by_category[1].totals.minutes, 30);
+        assert_eq!(by_category[1].average_cost(), Money::dollars(43));
+
+        let by_tag = group(&meetings, GroupBy::Tag);
+        let q3 = by_tag.iter().find(|g| g.key.as_deref() == Some("q3-launch")).unwrap();
+        assert_eq!(q3.totals.meetings, 3);
+        assert_eq!(q3.totals.cost, Money::dollars(258));
+    }
+
+    #[test]
+    fn test_by_team_splits_cross_team_meetings() {
+        let team = |name: &str, department: &str| Team {
+            name: name.to_string(),
+            department: Some(department.to_string()),
+        };
+        let config = Config {
+            teams: vec![team("platform", "engineering"), team("mobile", "engineering"), team("growth", "product")],
+            ..Default::default()
+        };
+        // Two platform engineers plus a product manager from growth.
+        let mut sync = meeting(19, 60, &[(Role::new("engineer"), 2), (Role::new("product_manager"), 1)], 158);
+        sync.team = Some("platform".to_string());
+        sync.people = vec![Participant {
+            name: "Sam".to_string(),
+            role: Role::new("product_manager"),
+            team: Some("growth".to_string()),
+        }];
+        let mut standup = meeting(20, 30, &[(Role::new("engineer"), 2)], 43);
+        standup.team = Some("mobile".to_string());
+        let unowned = meeting(21, 30, &[(Role::new("engineer"), 1)], 21);
+
+        let rollup = by_team(&[sync, standup, unowned], &config).unwrap();
+        let departments: Vec<(Option<&str>, usize, Money)> = rollup
+            .iter()
+            .map(|d| (d.department.as_deref(), d.totals.meetings, d.totals.cost))
+            .collect();
+        assert_eq!(
+            departments,
+            vec![
+                (Some("engineering"), 2, Money::dollars(129)),
+                (Some("product"), 1, Money::dollars(72)),
+                (None, 1, Money::dollars(21)),
+            ]
+        );
+        let teams: Vec<(Option<&str>, Money)> = rollup[0]
+            .teams
+            .iter()
+            .map(|t| (t.key.as_deref(), t.totals.cost))
+            .collect();
+        assert_eq!(teams, vec![(Some("platform"), Money::dollars(86)), (Some("mobile"), Money::dollars(43))]);
+        assert_eq!(rollup[2].teams[0].key, None);
+    }
+
+    #[test]
+    fn test_week_and_month_ranges() {
+        let wednesday = NaiveDate::from_ymd_opt(2026, 1, 21).unwrap();
+
+        let (start, end) = week_range(wednesday).unwrap();
+        assert_eq!(start.date_naive(), NaiveDate::from_ymd_opt(2026, 1, 19).unwrap());
+        assert_eq!(end.date_naive(), NaiveDate::from_ymd_opt(2026, 1, 26).unwrap());
+
+        let (start, end) = month_range(wednesday).unwrap();
+        assert_eq!(start.date_naive(), NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+        assert_eq!(end.date_naive(), NaiveDate::from_ymd_opt(2026, 2, 1).unwrap());
+    }
+}
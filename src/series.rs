@@ -0,0 +1,232 @@
+//! `series.rs`
+//
+// Recurring meetings. A `Series` describes a standing meeting (how often it
+// happens, when, for how long and who attends); the functions here expand it
+// into individual occurrences and project what it costs over a week, a
+// quarter and a year. Persistence lives in `ConfigManager`, and turning
+// occurrences into stored meetings in `commands::series`.
+
+use anyhow::Result;
+use chrono::{Duration, Months, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::calculator::Calculator;
+use crate::models::{Meeting, Role};
+use crate::money::Money;
+use crate::time_parser;
+
+/// How often a series repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Recurrence {
+    Weekly,
+    Biweekly,
+    /// Same day of the month, clamped to the last day in shorter months.
+    Monthly,
+}
+
+impl Recurrence {
+    pub fn per_year(self) -> f64 {
+        match self {
+            Recurrence::Weekly => 52.0,
+            Recurrence::Biweekly => 26.0,
+            Recurrence::Monthly => 12.0,
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Weekly => write!(f, "weekly"),
+            Recurrence::Biweekly => write!(f, "biweekly"),
+            Recurrence::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+/// A standing meeting, declared in `series.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Series {
+    pub name: String,
+    pub every: Recurrence,
+    /// Local start of the first occurrence; later ones keep its time of day.
+    pub first: NaiveDateTime,
+    pub duration_minutes: i64,
+    /// Last day an occurrence may fall on, if the series has ended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Start of the latest occurrence already saved to history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub materialized_through: Option<NaiveDateTime>,
+    pub attendees: BTreeMap<Role, u32>,
+}
+
+impl Series {
+    /// Start of the `n`th occurrence, counting from zero.
+    fn nth(&self, n: u32) -> Option<NaiveDateTime> {
+        let start = match self.every {
+            Recurrence::Weekly => self.first + Duration::weeks(n as i64),
+            Recurrence::Biweekly => self.first + Duration::weeks(2 * n as i64),
+            Recurrence::Monthly => self.first.checked_add_months(Months::new(n))?,
+        };
+        match self.until {
+            Some(until) if start.date() > until => None,
+            _ => Some(start),
+        }
+    }
+
+    /// Starts of every occurrence beginning in `[from, to)`, in order.
+    pub fn occurrences(&self, from: NaiveDateTime, to: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let mut starts = Vec::new();
+        for n in 0.. {
+            let Some(start) = self.nth(n) else { break };
+            if start >= to {
+                break;
+            }
+            if start >= from {
+                starts.push(start);
+            }
+        }
+        starts
+    }
+
+    /// The first occurrence starting at or after `now`, if the series hasn't ended.
+    pub fn next_occurrence(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        (0..).map_while(|n| self.nth(n)).find(|start| *start >= now)
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::minutes(self.duration_minutes)
+    }
+
+    pub fn attendee_map(&self) -> HashMap<Role, u32> {
+        self.attendees.clone().into_iter().collect()
+    }
+
+    /// Builds the meeting for the occurrence starting at `start`.
+    pub fn meeting_at(&self, start: NaiveDateTime, calculator: &Calculator) -> Result<Meeting> {
+        let start_time = time_parser::to_local(start)?;
+        let end_time = start_time + self.duration();
+        let mut meeting = Meeting::new(start_time, end_time, self.attendee_map(), Money::ZERO);
+        calculator.update_meeting_cost(&mut meeting)?;
+        meeting.title = Some(self.title.clone().unwrap_or_else(|| self.name.clone()));
+        meeting.category = self.category.clone();
+        meeting.tags = self.tags.clone();
+        meeting.series = Some(self.name.clone());
+        Ok(meeting)
+    }
+}
+
+/// What a series costs if it keeps running at its current rates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projection {
+    pub per_occurrence: Money,
+    pub per_week: Money,
+    pub per_quarter: Money,
+    pub per_year: Money,
+    pub person_hours_per_year: f64,
+}
+
+/// Projects `series` forward, pricing an occurrence at `at` with `calculator`.
+pub fn project(series: &Series, calculator: &Calculator, at: NaiveDateTime) -> Result<Projection> {
+    let per_occurrence = series.meeting_at(at, calculator)?.cost;
+    let per_year = per_occurrence.mul_f64(series.every.per_year());
+    let head_count: u32 = series.attendees.values().sum();
+
+    Ok(Projection {
+        per_occurrence,
+        per_week: per_year.div_round(52),
+        per_quarter: per_year.div_round(4),
+        per_year,
+        person_hours_per_year: head_count as f64 * series.duration_minutes as f64 / 60.0
+            * series.every.per_year(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Config;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn series(every: Recurrence, first: NaiveDateTime) -> Series {
+        Series {
+            name: "weekly-sync".to_string(),
+            every,
+            first,
+            duration_minutes: 30,
+            until: None,
+            title: None,
+            category: None,
+            tags: Vec::new(),
+            materialized_through: None,
+            attendees: [(Role::new("engineer"), 4), (Role::new("director"), 1)].into(),
+        }
+    }
+
+    #[test]
+    fn test_weekly_and_biweekly_occurrences() {
+        let weekly = series(Recurrence::Weekly, at("2026-10-05", "10:00"));
+        let starts = weekly.occurrences(at("2026-10-01", "00:00"), at("2026-10-27", "00:00"));
+        assert_eq!(
+            starts,
+            vec![
+                at("2026-10-05", "10:00"),
+                at("2026-10-12", "10:00"),
+                at("2026-10-19", "10:00"),
+                at("2026-10-26", "10:00"),
+            ]
+        );
+
+        let biweekly = series(Recurrence::Biweekly, at("2026-10-05", "10:00"));
+        let starts = biweekly.occurrences(at("2026-10-06", "00:00"), at("2026-11-03", "00:00"));
+        assert_eq!(
+            starts,
+            vec![at("2026-10-19", "10:00"), at("2026-11-02", "10:00")]
+        );
+    }
+
+    #[test]
+    fn test_monthly_clamps_and_until() {
+        let mut monthly = series(Recurrence::Monthly, at("2026-01-31", "09:00"));
+        monthly.until = Some(NaiveDate::from_ymd_opt(2026, 3, 30).unwrap());
+
+        let starts = monthly.occurrences(at("2026-01-01", "00:00"), at("2027-01-01", "00:00"));
+        assert_eq!(
+            starts,
+            vec![at("2026-01-31", "09:00"), at("2026-02-28", "09:00")]
+        );
+        assert_eq!(monthly.next_occurrence(at("2026-03-01", "00:00")), None);
+    }
+
+    #[test]
+    fn test_projection() {
+        let config = Config::default();
+        let calculator = Calculator::new(&config);
+        let weekly = series(Recurrence::Weekly, at("2026-10-05", "10:00"));
+
+        // 4 engineers @ $43 + 1 director @ $105 = $277/hr, so $138.50 a week.
+        let projection = project(&weekly, &calculator, weekly.first).unwrap();
+        assert_eq!(projection.per_occurrence, Money::from_f64(138.5));
+        assert_eq!(projection.per_week, Money::from_f64(138.5));
+        assert_eq!(projection.per_year, Money::from_f64(138.5 * 52.0));
+        assert_eq!(projection.per_quarter, Money::from_f64(138.5 * 13.0));
+        assert_eq!(projection.person_hours_per_year, 5.0 * 0.5 * 52.0);
+
+        let monthly = series(Recurrence::Monthly, at("2026-10-05", "10:00"));
+        let projection = project(&monthly, &calculator, monthly.first).unwrap();
+        assert_eq!(projection.per_year, Money::from_f64(138.5 * 12.0));
+    }
+}
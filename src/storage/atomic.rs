@@ -0,0 +1,95 @@
+//! Crash- and race-safe file helpers shared by the file-based stores.
+
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const LOCK_FILE: &str = ".lock";
+
+/// An exclusive advisory lock on a data or config directory, released on drop.
+///
+/// Every process that modifies files in the directory takes this lock first,
+/// so two terminals saving at once serialize instead of losing a write.
+pub struct DirLock {
+    _file: File,
+}
+
+impl DirLock {
+    /// Blocks until the lock on `dir` is acquired.
+    pub fn acquire(dir: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(dir.join(LOCK_FILE))
+            .with_context(|| format!("Failed to open the lock file in {}", dir.display()))?;
+        file.lock().with_context(|| format!("Failed to lock {}", dir.display()))?;
+        Ok(Self { _file: file })
+    }
+}
+
+/// Replaces `path` with `contents` without ever leaving a half-written file.
+///
+/// The data goes to a temporary file in the same directory, is flushed to
+/// disk, and is then renamed over `path`.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = path
+        .file_name()
+        .context("Cannot write to a path without a file name")?
+        .to_string_lossy();
+    let tmp_path = dir.join(format!(".{}.tmp-{}", file_name, std::process::id()));
+
+    let result = (|| {
+        let mut tmp = File::create(&tmp_path).context("Failed to create temporary file")?;
+        tmp.write_all(contents)
+            .context("Failed to write temporary file")?;
+        tmp.sync_all().context("Failed to flush temporary file")?;
+        fs::rename(&tmp_path, path).context("Failed to replace file")
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Copies `path` to a sibling named `<file name>.<suffix>` and returns it.
+pub fn backup_copy(path: &Path, suffix: &str) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .context("Cannot back up a path without a file name")?
+        .to_string_lossy();
+    let backup = path.with_file_name(format!("{}.{}", file_name, suffix));
+    fs::copy(path, &backup).with_context(|| format!("Failed to back up {}", path.display()))?;
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_replaces_contents() {
+        let dir = std::env::temp_dir().join(format!("meeting-meter-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("meetings.json");
+
+        write_atomic(&path, b"[1]").unwrap();
+        {
+            let _lock = DirLock::acquire(&dir).unwrap();
+            write_atomic(&path, b"[1, 2]").unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[1, 2]");
+        let leftovers: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".tmp-"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
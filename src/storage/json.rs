@@ -0,0 +1,343 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::Local;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::atomic::{DirLock, backup_copy, write_atomic};
+use super::schema::{self, MEETINGS_SCHEMA_VERSION};
+use super::{MeetingStore, TrashedMeeting};
+use crate::models::Meeting;
+
+/// Stores every meeting in one pretty-printed JSON file, with deleted
+/// meetings kept in a sibling `trash.json`.
+pub struct JsonStore {
+    meetings_file: PathBuf,
+    trash_file: PathBuf,
+}
+
+/// What `JsonStore::repair` found and did.
+pub enum RepairOutcome {
+    /// The file parsed cleanly; nothing was changed.
+    Healthy { meetings: usize },
+    /// The file was damaged. It was moved to `backup` and replaced with the
+    /// `salvaged` meetings that could still be read.
+    Repaired { salvaged: usize, backup: PathBuf },
+}
+
+impl JsonStore {
+    pub fn new(meetings_file: PathBuf) -> Self {
+        let trash_file = meetings_file.with_file_name("trash.json");
+        Self {
+            meetings_file,
+            trash_file,
+        }
+    }
+
+    fn data_dir(&self) -> &Path {
+        self.meetings_file.parent().unwrap_or_else(|| Path::new("."))
+    }
+
+    /// Reads a meetings-style file, upgrading older layouts in memory. Also
+    /// returns the version the file was written in.
+    fn read<T: DeserializeOwned>(path: &Path) -> Result<(Vec<T>, u32)> {
+        if !path.exists() {
+            return Ok((Vec::new(), MEETINGS_SCHEMA_VERSION));
+        }
+
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        schema::from_document(&contents).with_context(|| {
+            format!(
+                "Failed to parse {}. Run `meeting-meter doctor` to recover it.",
+                path.display()
+            )
+        })
+    }
+
+    fn write<T: Serialize>(path: &Path, items: &[T]) -> Result<()> {
+        let contents = schema::to_document(items)?;
+        write_atomic(path, contents.as_bytes())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Applies `change` to the stored items under the data directory lock and
+    /// writes the result back, keeping a backup if the file was upgraded.
+    fn modify<T, R>(path: &Path, change: impl FnOnce(&mut Vec<T>) -> Result<R>) -> Result<R>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let (mut items, from_version) = Self::read(path)?;
+        let result = change(&mut items)?;
+        if from_version < MEETINGS_SCHEMA_VERSION {
+            backup_copy(path, &format!("v{}.bak", from_version))?;
+        }
+        Self::write(path, &items)?;
+        Ok(result)
+    }
+
+    /// Every meeting and trashed meeting, read without upgrading or otherwise
+    /// rewriting either file.
+    pub fn export(&self) -> Result<(Vec<Meeting>, Vec<TrashedMeeting>)> {
+        let _lock = DirLock::acquire(self.data_dir())?;
+        let (meetings, _) = Self::read(&self.meetings_file)?;
+        let (trash, _) = Self::read(&self.trash_file)?;
+        Ok((meetings, trash))
+    }
+
+    /// Checks the meetings file and, if it no longer parses, rewrites it with
+    /// every meeting that can still be recovered.
+    pub fn repair(&self) -> Result<RepairOutcome> {
+        let _lock = DirLock::acquire(self.data_dir())?;
+
+        if !self.meetings_file.exists() {
+            return Ok(RepairOutcome::Healthy { meetings: 0 });
+        }
+
+        let contents =
+            fs::read_to_string(&self.meetings_file).context("Failed to read meetings file")?;
+        if let Ok((meetings, _)) = schema::from_document::<Meeting>(&contents) {
+            return Ok(RepairOutcome::Healthy {
+                meetings: meetings.len(),
+            });
+        }
+
+        let salvaged = salvage_meetings(&contents);
+
+        let backup = backup_copy(
+            &self.meetings_file,
+            &format!("corrupt-{}", Local::now().format("%Y%m%d%H%M%S")),
+        )?;
+        Self::write(&self.meetings_file, &salvaged)?;
+
+        Ok(RepairOutcome::Repaired {
+            salvaged: salvaged.len(),
+            backup,
+        })
+    }
+}
+
+impl MeetingStore for JsonStore {
+    fn save_meeting(&self, meeting: &Meeting) -> Result<()> {
+        let _lock = DirLock::acquire(self.data_dir())?;
+        Self::modify(&self.meetings_file, |meetings: &mut Vec<Meeting>| {
+            meetings.push(meeting.clone());
+            Ok(())
+        })
+    }
+
+    fn load_all_meetings(&self) -> Result<Vec<Meeting>> {
+        let (meetings, from_version) = Self::read(&self.meetings_file)?;
+
+        if from_version < MEETINGS_SCHEMA_VERSION {
+            let _lock = DirLock::acquire(self.data_dir())?;
+            // Another process may have upgraded the file while we waited.
+            return Self::modify(&self.meetings_file, |meetings: &mut Vec<Meeting>| {
+                Ok(meetings.clone())
+            });
+        }
+
+        Ok(meetings)
+    }
+
+    fn update_meetings(&self, updated: &[Meeting]) -> Result<()> {
+        let _lock = DirLock::acquire(self.data_dir())?;
+        Self::modify(&self.meetings_file, |meetings: &mut Vec<Meeting>| {
+            for meeting in updated {
+                let existing = meetings
+                    .iter_mut()
+                    .find(|m| m.id == meeting.id)
+                    .ok_or_else(|| anyhow!("No meeting with id {}", meeting.id))?;
+                *existing = meeting.clone();
+            }
+            Ok(())
+        })
+    }
+
+    fn trash_meeting(&self, id: &str) -> Result<Meeting> {
+        let _lock = DirLock::acquire(self.data_dir())?;
+
+        let (meetings, _) = Self::read::<Meeting>(&self.meetings_file)?;
+        let meeting = meetings
+            .into_iter()
+            .find(|m| m.id == id)
+            .ok_or_else(|| anyhow!("No meeting with id {}", id))?;
+
+        // Trash first: if we crash in between, the meeting is duplicated
+        // rather than lost.
+        Self::modify(&self.trash_file, |trash: &mut Vec<TrashedMeeting>| {
+            trash.push(TrashedMeeting {
+                deleted_at: Local::now(),
+                meeting: meeting.clone(),
+            });
+            Ok(())
+        })?;
+        Self::modify(&self.meetings_file, |meetings: &mut Vec<Meeting>| {
+            meetings.retain(|m| m.id != id);
+            Ok(())
+        })?;
+
+        Ok(meeting)
+    }
+
+    fn load_trash(&self) -> Result<Vec<TrashedMeeting>> {
+        Ok(Self::read(&self.trash_file)?.0)
+    }
+
+    fn restore_meeting(&self, id: &str) -> Result<Meeting> {
+        let _lock = DirLock::acquire(self.data_dir())?;
+
+        let (trash, _) = Self::read::<TrashedMeeting>(&self.trash_file)?;
+        let meeting = trash
+            .into_iter()
+            .find(|t| t.meeting.id == id)
+            .map(|t| t.meeting)
+            .ok_or_else(|| anyhow!("No meeting with id {} in the trash", id))?;
+
+        Self::modify(&self.meetings_file, |meetings: &mut Vec<Meeting>| {
+            if !meetings.iter().any(|m| m.id == id) {
+                meetings.push(meeting.clone());
+            }
+            Ok(())
+        })?;
+        Self::modify(&self.trash_file, |trash: &mut Vec<TrashedMeeting>| {
+            trash.retain(|t| t.meeting.id != id);
+            Ok(())
+        })?;
+
+        Ok(meeting)
+    }
+
+    fn location(&self) -> PathBuf {
+        self.meetings_file.clone()
+    }
+}
+
+/// Recovers every complete meeting object from a damaged meetings file,
+/// e.g. one truncated by a crash mid-write. Works on every file layout, since
+/// meetings are always objects inside an array; objects that don't parse as a
+/// `Meeting` are skipped.
+pub fn salvage_meetings(contents: &str) -> Vec<Meeting> {
+    let mut meetings = Vec::new();
+    let mut containers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    // Start offset and nesting depth of the array element being captured.
+    let mut candidate: Option<(usize, usize)> = None;
+
+    for (i, c) in contents.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '[' | '{' => {
+                if c == '{' && candidate.is_none() && containers.last() == Some(&'[') {
+                    candidate = Some((i, containers.len()));
+                }
+                containers.push(c);
+            }
+            ']' | '}' => {
+                containers.pop();
+                if let Some((start, depth)) = candidate
+                    && depth == containers.len()
+                {
+                    candidate = None;
+                    if let Ok(meeting) = serde_json::from_str::<Meeting>(&contents[start..=i]) {
+                        meetings.push(meeting);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    meetings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Role;
+    use crate::money::Money;
+    use chrono::{Duration, Local};
+    use std::collections::HashMap;
+
+    fn sample_meeting(notes: &str) -> Meeting {
+        let start = Local::now();
+        let mut attendees = HashMap::new();
+        attendees.insert(Role::new("engineer"), 2);
+        let mut meeting = Meeting::new(start, start + Duration::minutes(30), attendees, Money::dollars(43));
+        meeting.notes = Some(notes.to_string());
+        meeting
+    }
+
+    #[test]
+    fn test_salvage_truncated_file() {
+        let meetings = vec![
+            sample_meeting("has a } brace and \"quotes\""),
+            sample_meeting("second"),
+            sample_meeting("third"),
+        ];
+        let contents = schema::to_document(&meetings).unwrap();
+        // Cut the file off partway through the last meeting.
+        let truncated = &contents[..contents.len() - 40];
+
+        let salvaged = salvage_meetings(truncated);
+        assert_eq!(salvaged.len(), 2);
+        assert_eq!(salvaged[0].id, meetings[0].id);
+        assert_eq!(salvaged[1].notes.as_deref(), Some("second"));
+    }
+
+    #[test]
+    fn test_salvage_skips_garbage() {
+        assert!(salvage_meetings("").is_empty());
+        assert!(salvage_meetings("[{\"id\": 1}, garbage").is_empty());
+    }
+
+    #[test]
+    fn test_salvage_v1_layout() {
+        let contents = include_str!("../../tests/fixtures/meetings_v1.json");
+        assert_eq!(salvage_meetings(&contents[..contents.len() - 20]).len(), 1);
+    }
+
+    #[test]
+    fn test_trash_and_restore() {
+        let dir = std::env::temp_dir().join(format!("meeting-meter-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let store = JsonStore::new(dir.join("meetings.json"));
+
+        let mut meeting = sample_meeting("retro");
+        store.save_meeting(&meeting).unwrap();
+        store.save_meeting(&sample_meeting("standup")).unwrap();
+
+        meeting.cost = Money::dollars(99);
+        store.update_meeting(&meeting).unwrap();
+        assert_eq!(store.find_by_id(&meeting.id[..8]).unwrap().cost, Money::dollars(99));
+
+        // A batch with an unknown id changes nothing.
+        let mut changed = meeting.clone();
+        changed.cost = Money::dollars(5);
+        assert!(store.update_meetings(&[changed, sample_meeting("missing")]).is_err());
+        assert_eq!(store.find_by_id(&meeting.id).unwrap().cost, Money::dollars(99));
+
+        store.trash_meeting(&meeting.id).unwrap();
+        assert_eq!(store.load_all_meetings().unwrap().len(), 1);
+        assert_eq!(store.load_trash().unwrap()[0].meeting.cost, Money::dollars(99));
+
+        store.restore_meeting(&meeting.id).unwrap();
+        assert_eq!(store.load_all_meetings().unwrap().len(), 2);
+        assert!(store.load_trash().unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,365 @@
+mod atomic;
+mod json;
+mod schema;
+mod sqlite;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Local};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::models::{Config, Meeting, Role};
+use crate::money::Money;
+
+pub use atomic::{DirLock, backup_copy, write_atomic};
+pub use json::{JsonStore, RepairOutcome};
+pub use sqlite::SqliteStore;
+
+/// Which on-disk format meetings are kept in, selected by `storage` in `config.toml`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    /// A single `meetings.json` file, rewritten on every save.
+    #[default]
+    Json,
+    /// An embedded SQLite database, `meetings.db`, indexed by start time.
+    Sqlite,
+}
+
+impl fmt::Display for StorageBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageBackend::Json => write!(f, "json"),
+            StorageBackend::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+/// Persistence for logged meetings.
+pub trait MeetingStore {
+    fn save_meeting(&self, meeting: &Meeting) -> Result<()>;
+
+    fn load_all_meetings(&self) -> Result<Vec<Meeting>>;
+
+    /// Replaces the stored meeting that has the same id.
+    fn update_meeting(&self, meeting: &Meeting) -> Result<()> {
+        self.update_meetings(std::slice::from_ref(meeting))
+    }
+
+    /// Replaces each stored meeting that has the same id as one in
+    /// `meetings`, all at once. Nothing is changed if any id is missing.
+    fn update_meetings(&self, meetings: &[Meeting]) -> Result<()>;
+
+    /// Moves the meeting with this exact id to the trash and returns it.
+    fn trash_meeting(&self, id: &str) -> Result<Meeting>;
+
+    fn load_trash(&self) -> Result<Vec<TrashedMeeting>>;
+
+    /// Moves the trashed meeting with this exact id back into history.
+    fn restore_meeting(&self, id: &str) -> Result<Meeting>;
+
+    /// Where the meetings live, for display purposes.
+    fn location(&self) -> PathBuf;
+
+    /// Looks up a meeting by a unique prefix of its id, like git does for
+    /// commit hashes.
+    fn find_by_id(&self, prefix: &str) -> Result<Meeting> {
+        let meetings = self.load_all_meetings()?;
+        let id = resolve_id(meetings.iter().map(|m| m.id.as_str()), prefix)?;
+        meetings
+            .into_iter()
+            .find(|m| m.id == id)
+            .ok_or_else(|| anyhow!("No meeting matches '{}'", prefix))
+    }
+
+    /// Returns every meeting matching `filter`, newest first.
+    fn find_meetings(&self, filter: &MeetingFilter) -> Result<Vec<Meeting>> {
+        let mut meetings: Vec<Meeting> = self
+            .load_all_meetings()?
+            .into_iter()
+            .filter(|meeting| filter.matches(meeting))
+            .collect();
+        meetings.sort_by_key(|m| std::cmp::Reverse(m.start_time));
+        Ok(meetings)
+    }
+
+    /// Returns the most recent meetings matching `filter`, newest first.
+    fn get_recent_meetings(&self, filter: &MeetingFilter, limit: usize) -> Result<Vec<Meeting>> {
+        let meetings = self.find_meetings(filter)?;
+        Ok(meetings.into_iter().take(limit).collect())
+    }
+}
+
+/// A deleted meeting, kept so it can be restored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedMeeting {
+    pub deleted_at: DateTime<Local>,
+    #[serde(flatten)]
+    pub meeting: Meeting,
+}
+
+/// Expands `prefix` to the one id in `ids` that starts with it.
+pub fn resolve_id<'a>(ids: impl Iterator<Item = &'a str>, prefix: &str) -> Result<String> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.is_empty() {
+        return Err(anyhow!("Please give a meeting id (or the start of one)"));
+    }
+
+    let matches: Vec<&str> = ids.filter(|id| id.starts_with(&prefix)).collect();
+    match matches.as_slice() {
+        [id] => Ok(id.to_string()),
+        [] => Err(anyhow!("No meeting matches '{}'", prefix)),
+        _ => Err(anyhow!(
+            "'{}' is ambiguous; it matches {} meetings. Use more characters.",
+            prefix,
+            matches.len()
+        )),
+    }
+}
+
+/// Opens the backend selected in `config`.
+pub fn open(config: &Config) -> Result<Box<dyn MeetingStore>> {
+    open_backend(config.storage)
+}
+
+pub fn open_backend(backend: StorageBackend) -> Result<Box<dyn MeetingStore>> {
+    Ok(match backend {
+        StorageBackend::Json => Box::new(open_json()?),
+        StorageBackend::Sqlite => Box::new(open_sqlite()?),
+    })
+}
+
+pub fn open_json() -> Result<JsonStore> {
+    Ok(JsonStore::new(data_dir()?.join("meetings.json")))
+}
+
+pub fn open_sqlite() -> Result<SqliteStore> {
+    SqliteStore::open(&data_dir()?.join("meetings.db"))
+}
+
+fn data_dir() -> Result<PathBuf> {
+    let proj_dirs = ProjectDirs::from("com", "meeting-meter", "meeting-meter")
+        .context("Failed to determine data directory")?;
+
+    let data_dir = proj_dirs.data_dir().to_path_buf();
+    fs::create_dir_all(&data_dir).context("Failed to create data directory")?;
+
+    Ok(data_dir)
+}
+
+/// Criteria used to narrow down the meetings returned by a query.
+///
+/// Every field is optional; an empty filter matches every meeting.
+#[derive(Debug, Clone, Default)]
+pub struct MeetingFilter {
+    /// Only include meetings starting at or after this instant.
+    pub since: Option<DateTime<Local>>,
+    /// Only include meetings starting strictly before this instant.
+    pub until: Option<DateTime<Local>>,
+    pub min_cost: Option<MinCost>,
+    /// Only include meetings with at least one attendee of this role.
+    pub role: Option<Role>,
+    /// Case-insensitive substring to look for in the meeting title and notes.
+    pub search: Option<String>,
+    /// Only include meetings carrying this tag (in `normalize_label` form).
+    pub tag: Option<String>,
+    /// Only include meetings in this category (in `normalize_label` form).
+    pub category: Option<String>,
+}
+
+impl MeetingFilter {
+    pub fn matches(&self, meeting: &Meeting) -> bool {
+        if let Some(since) = self.since
+            && meeting.start_time < since
+        {
+            return false;
+        }
+        if let Some(until) = self.until
+            && meeting.start_time >= until
+        {
+            return false;
+        }
+        if let Some(min_cost) = &self.min_cost
+            && !min_cost.allows(meeting)
+        {
+            return false;
+        }
+        if let Some(role) = &self.role
+            && meeting.attendees.get(role).is_none_or(|count| *count == 0)
+        {
+            return false;
+        }
+        if let Some(search) = &self.search {
+            let needle = search.to_lowercase();
+            let found = [&meeting.title, &meeting.notes]
+                .into_iter()
+                .flatten()
+                .any(|text| text.to_lowercase().contains(&needle));
+            if !found {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag
+            && !meeting.tags.contains(tag)
+        {
+            return false;
+        }
+        if let Some(category) = &self.category
+            && meeting.category.as_ref() != Some(category)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// A lowest cost in the display currency. Meetings priced in another
+/// currency are converted before they are compared with it.
+#[derive(Debug, Clone)]
+pub struct MinCost {
+    amount: Money,
+    config: Config,
+}
+
+impl MinCost {
+    pub fn new(amount: Money, config: &Config) -> Self {
+        Self {
+            amount,
+            config: config.clone(),
+        }
+    }
+
+    /// Whether `meeting` costs at least the minimum. A meeting in a currency
+    /// that no longer has an exchange rate can't be compared, so it is kept
+    /// for the caller to report.
+    pub fn allows(&self, meeting: &Meeting) -> bool {
+        match self.config.to_display(meeting.cost, &meeting.currency) {
+            Ok(cost) => cost >= self.amount,
+            Err(_) => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use std::collections::HashMap;
+
+    fn meeting_at(day: u32, cost: i64, role: Role, notes: Option<&str>) -> Meeting {
+        let start = Local.with_ymd_and_hms(2026, 1, day, 14, 0, 0).unwrap();
+        let mut attendees = HashMap::new();
+        attendees.insert(role, 2);
+        let mut meeting = Meeting::new(start, start + Duration::hours(1), attendees, Money::dollars(cost));
+        meeting.notes = notes.map(str::to_string);
+        meeting
+    }
+
+    #[test]
+    fn test_resolve_id_prefix() {
+        let ids = ["5d0f6a4e-3c1b", "5d1a0000-0000", "a8e2b7c4-91d3"];
+
+        assert_eq!(resolve_id(ids.into_iter(), "a8").unwrap(), "a8e2b7c4-91d3");
+        assert_eq!(resolve_id(ids.into_iter(), "5D0F").unwrap(), "5d0f6a4e-3c1b");
+        assert!(resolve_id(ids.into_iter(), "5d").is_err());
+        assert!(resolve_id(ids.into_iter(), "ff").is_err());
+        assert!(resolve_id(ids.into_iter(), "").is_err());
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let meeting = meeting_at(10, 100, Role::new("engineer"), None);
+        assert!(MeetingFilter::default().matches(&meeting));
+    }
+
+    #[test]
+    fn test_filter_by_date_range() {
+        let meeting = meeting_at(10, 100, Role::new("engineer"), None);
+        let filter = MeetingFilter {
+            since: Some(Local.with_ymd_and_hms(2026, 1, 10, 0, 0, 0).unwrap()),
+            until: Some(Local.with_ymd_and_hms(2026, 1, 11, 0, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&meeting));
+
+        let filter = MeetingFilter {
+            since: Some(Local.with_ymd_and_hms(2026, 1, 11, 0, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&meeting));
+    }
+
+    #[test]
+    fn test_filter_by_cost_role_and_notes() {
+        let meeting = meeting_at(10, 100, Role::new("engineer"), Some("Sprint Planning"));
+
+        let filter = MeetingFilter {
+            min_cost: Some(MinCost::new(Money::dollars(150), &Config::default())),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&meeting));
+
+        let filter = MeetingFilter {
+            role: Some(Role::new("director")),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&meeting));
+
+        let filter = MeetingFilter {
+            role: Some(Role::new("engineer")),
+            search: Some("planning".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&meeting));
+    }
+
+    #[test]
+    fn test_min_cost_converts_currencies() {
+        let mut config = Config {
+            currency: "EUR".parse().unwrap(),
+            ..Default::default()
+        };
+        config.exchange_rates.insert("EUR".parse().unwrap(), 1.25);
+        config.exchange_rates.insert("JPY".parse().unwrap(), 0.01);
+        let filter = MeetingFilter {
+            min_cost: Some(MinCost::new(Money::dollars(90), &config)),
+            ..Default::default()
+        };
+
+        // $120 is €96, but ¥1000 is only €8.
+        let dollars = meeting_at(10, 120, Role::new("engineer"), None);
+        let mut yen = meeting_at(11, 1000, Role::new("engineer"), None);
+        yen.currency = "JPY".parse().unwrap();
+        let mut euros = meeting_at(12, 80, Role::new("engineer"), None);
+        euros.currency = "EUR".parse().unwrap();
+
+        assert!(filter.matches(&dollars));
+        assert!(!filter.matches(&yen));
+        assert!(!filter.matches(&euros));
+    }
+
+    #[test]
+    fn test_filter_by_labels() {
+        let mut meeting = meeting_at(10, 100, Role::new("engineer"), None);
+        meeting.title = Some("Q3 Roadmap".to_string());
+        meeting.category = Some("planning".to_string());
+        meeting.tags = vec!["q3-launch".to_string()];
+
+        let filter = MeetingFilter {
+            search: Some("roadmap".to_string()),
+            tag: Some("q3-launch".to_string()),
+            category: Some("planning".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&meeting));
+
+        let filter = MeetingFilter {
+            category: Some("retro".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&meeting));
+    }
+}
@@ -0,0 +1,152 @@
+//! Versioning for the meetings file (and the trash file, which shares its layout).
+//!
+//! 1. A bare JSON array of meetings (up to 0.4).
+//! 2. An object with a `schema_version` and a `meetings` array.
+//! 3. Meetings can carry the `currency` their cost is in, named `people`,
+//!    an owning `team`, partial `attendance` and `pauses`. `start_time` and
+//!    `end_time` are the real start and end, and `cost` leaves out the
+//!    paused time. The layout is otherwise unchanged; the bump keeps older
+//!    versions, which would drop the new fields on their next save, from
+//!    opening the file.
+
+use anyhow::{Context, Result, anyhow};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const MEETINGS_SCHEMA_VERSION: u32 = 3;
+
+/// Upgrades from version `n` to `n + 1` live at index `n - 1`.
+const MIGRATIONS: [fn(Value) -> Result<Value>; 2] = [migrate_v1_to_v2, migrate_v2_to_v3];
+
+#[derive(Serialize)]
+struct MeetingsFileRef<'a, T> {
+    schema_version: u32,
+    meetings: &'a [T],
+}
+
+#[derive(Deserialize)]
+struct MeetingsFile<T> {
+    meetings: Vec<T>,
+}
+
+/// Serializes `meetings` in the current file layout.
+pub fn to_document<T: Serialize>(meetings: &[T]) -> Result<String> {
+    serde_json::to_string_pretty(&MeetingsFileRef {
+        schema_version: MEETINGS_SCHEMA_VERSION,
+        meetings,
+    })
+    .context("Failed to serialize meetings")
+}
+
+/// Parses a meetings file of any known version, returning the meetings and
+/// the version the file was written in.
+pub fn from_document<T: DeserializeOwned>(contents: &str) -> Result<(Vec<T>, u32)> {
+    let mut doc: Value = serde_json::from_str(contents)?;
+    let from_version = detect_version(&doc)?;
+
+    if from_version > MEETINGS_SCHEMA_VERSION {
+        return Err(anyhow!(
+            "Meetings file uses schema version {}, but this meeting-meter only understands up to {}. Please upgrade meeting-meter.",
+            from_version,
+            MEETINGS_SCHEMA_VERSION
+        ));
+    }
+
+    for version in from_version..MEETINGS_SCHEMA_VERSION {
+        doc = MIGRATIONS[(version - 1) as usize](doc)
+            .with_context(|| format!("Failed to upgrade meetings from version {}", version))?;
+    }
+
+    let file: MeetingsFile<T> = serde_json::from_value(doc)?;
+    Ok((file.meetings, from_version))
+}
+
+fn detect_version(doc: &Value) -> Result<u32> {
+    match doc {
+        Value::Array(_) => Ok(1),
+        Value::Object(map) => map
+            .get("schema_version")
+            .and_then(Value::as_u64)
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| anyhow!("Meetings file has no valid schema_version")),
+        _ => Err(anyhow!("Meetings file is neither a list nor an object")),
+    }
+}
+
+fn migrate_v1_to_v2(doc: Value) -> Result<Value> {
+    Ok(serde_json::json!({
+        "schema_version": 2,
+        "meetings": doc,
+    }))
+}
+
+/// Only new optional fields, so the meetings load as they are.
+fn migrate_v2_to_v3(mut doc: Value) -> Result<Value> {
+    doc["schema_version"] = Value::from(3);
+    Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Meeting;
+    use crate::money::Money;
+
+    #[test]
+    fn test_load_v1_meetings() {
+        let (meetings, from_version) =
+            from_document::<Meeting>(include_str!("../../tests/fixtures/meetings_v1.json")).unwrap();
+
+        assert_eq!(from_version, 1);
+        assert_eq!(meetings.len(), 2);
+        assert_eq!(meetings[1].notes.as_deref(), Some("Roadmap review"));
+        assert_eq!(meetings[1].duration_minutes(), 75);
+    }
+
+    #[test]
+    fn test_load_v2_meetings() {
+        let (meetings, from_version) =
+            from_document::<Meeting>(include_str!("../../tests/fixtures/meetings_v2.json")).unwrap();
+
+        assert_eq!(from_version, 2);
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].cost, Money::from_f64(301.5));
+    }
+
+    #[test]
+    fn test_load_v3_meetings() {
+        let (meetings, from_version) =
+            from_document::<Meeting>(include_str!("../../tests/fixtures/meetings_v3.json")).unwrap();
+
+        assert_eq!(from_version, 3);
+        assert_eq!(meetings.len(), 1);
+        let meeting = &meetings[0];
+        assert_eq!(meeting.pauses.len(), 1);
+        assert_eq!(meeting.wall_clock_duration().num_minutes(), 60);
+        assert_eq!(meeting.duration_minutes(), 50);
+        assert_eq!(meeting.currency.code(), "EUR");
+        assert_eq!(meeting.team.as_deref(), Some("platform"));
+        assert_eq!(meeting.people[0].name, "Priya");
+        assert_eq!(meeting.attendance[0].name.as_deref(), Some("Priya"));
+
+        // Saving keeps every field.
+        let (reloaded, _) = from_document::<Meeting>(&to_document(&meetings).unwrap()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&reloaded).unwrap(),
+            serde_json::to_value(&meetings).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_and_reject_newer() {
+        let (meetings, _) =
+            from_document::<Meeting>(include_str!("../../tests/fixtures/meetings_v1.json")).unwrap();
+        let (reloaded, version) = from_document::<Meeting>(&to_document(&meetings).unwrap()).unwrap();
+        assert_eq!(version, MEETINGS_SCHEMA_VERSION);
+        assert_eq!(reloaded.len(), meetings.len());
+
+        assert!(from_document::<Meeting>(r#"{"schema_version": 99, "meetings": []}"#).is_err());
+    }
+}
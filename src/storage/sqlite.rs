@@ -0,0 +1,382 @@
+use anyhow::{Context, Result, anyhow};
+use rusqlite::{Connection, params, params_from_iter, types::Value};
+use chrono::Local;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::schema::MEETINGS_SCHEMA_VERSION;
+use super::{MeetingFilter, MeetingStore, TrashedMeeting};
+use crate::models::Meeting;
+
+/// Stores meetings in an embedded SQLite database.
+///
+/// Each row keeps the full meeting as JSON alongside the columns we query on,
+/// so new `Meeting` fields don't need a table change.
+pub struct SqliteStore {
+    conn: Connection,
+    path: PathBuf,
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meetings (
+        id       TEXT PRIMARY KEY,
+        start_ms INTEGER NOT NULL,
+        cost     REAL NOT NULL,
+        data     TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_meetings_start ON meetings (start_ms);
+    CREATE TABLE IF NOT EXISTS trash (
+        id         TEXT PRIMARY KEY,
+        deleted_ms INTEGER NOT NULL,
+        data       TEXT NOT NULL
+    );
+";
+
+impl SqliteStore {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path).context("Failed to open meetings database")?;
+        Self::init(conn, path.to_path_buf())
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?, PathBuf::from(":memory:"))
+    }
+
+    fn init(conn: Connection, path: PathBuf) -> Result<Self> {
+        // Another process may be mid-write; wait for it rather than failing.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to initialize meetings database")?;
+
+        // Rows hold meetings in the same layout as the JSON file, so the
+        // database shares its schema version.
+        let version: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version > MEETINGS_SCHEMA_VERSION {
+            return Err(anyhow!(
+                "Meetings database uses schema version {}, but this meeting-meter only understands up to {}. Please upgrade meeting-meter.",
+                version,
+                MEETINGS_SCHEMA_VERSION
+            ));
+        }
+        conn.pragma_update(None, "user_version", MEETINGS_SCHEMA_VERSION)?;
+
+        Ok(Self { conn, path })
+    }
+
+    /// Inserts `meetings`, skipping any whose id is already stored.
+    /// Returns how many were actually added.
+    pub fn import_meetings(&self, meetings: &[Meeting]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut added = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO meetings (id, start_ms, cost, data) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for meeting in meetings {
+                let data = serde_json::to_string(meeting).context("Failed to serialize meeting")?;
+                added += stmt.execute(params![
+                    meeting.id,
+                    meeting.start_time.timestamp_millis(),
+                    meeting.cost.to_f64(),
+                    data
+                ])?;
+            }
+        }
+        tx.commit().context("Failed to write meetings database")?;
+        Ok(added)
+    }
+
+    /// Inserts `trash` into the trash, skipping any meeting already there.
+    /// Returns how many were actually added.
+    pub fn import_trash(&self, trash: &[TrashedMeeting]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut added = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO trash (id, deleted_ms, data) VALUES (?1, ?2, ?3)",
+            )?;
+            for trashed in trash {
+                let data = serde_json::to_string(trashed).context("Failed to serialize meeting")?;
+                added += stmt.execute(params![
+                    trashed.meeting.id,
+                    trashed.deleted_at.timestamp_millis(),
+                    data
+                ])?;
+            }
+        }
+        tx.commit().context("Failed to write meetings database")?;
+        Ok(added)
+    }
+
+    /// Runs SQLite's own consistency check, returning the problems it reports.
+    pub fn integrity_check(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let messages = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(messages.into_iter().filter(|m| m != "ok").collect())
+    }
+
+    fn query<T: DeserializeOwned>(&self, sql: &str, args: Vec<Value>) -> Result<Vec<T>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params_from_iter(args), |row| row.get::<_, String>(0))?;
+
+        let mut meetings = Vec::new();
+        for data in rows {
+            let meeting = serde_json::from_str(&data?).context("Failed to parse stored meeting")?;
+            meetings.push(meeting);
+        }
+        Ok(meetings)
+    }
+}
+
+impl MeetingStore for SqliteStore {
+    fn save_meeting(&self, meeting: &Meeting) -> Result<()> {
+        let data = serde_json::to_string(meeting).context("Failed to serialize meeting")?;
+        self.conn
+            .execute(
+                "INSERT INTO meetings (id, start_ms, cost, data) VALUES (?1, ?2, ?3, ?4)",
+                params![meeting.id, meeting.start_time.timestamp_millis(), meeting.cost.to_f64(), data],
+            )
+            .context("Failed to write meetings database")?;
+        Ok(())
+    }
+
+    fn load_all_meetings(&self) -> Result<Vec<Meeting>> {
+        self.query("SELECT data FROM meetings ORDER BY start_ms", Vec::new())
+    }
+
+    fn update_meetings(&self, meetings: &[Meeting]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE meetings SET start_ms = ?2, cost = ?3, data = ?4 WHERE id = ?1",
+            )?;
+            for meeting in meetings {
+                let data = serde_json::to_string(meeting).context("Failed to serialize meeting")?;
+                let updated = stmt
+                    .execute(params![
+                        meeting.id,
+                        meeting.start_time.timestamp_millis(),
+                        meeting.cost.to_f64(),
+                        data
+                    ])
+                    .context("Failed to write meetings database")?;
+                if updated == 0 {
+                    return Err(anyhow!("No meeting with id {}", meeting.id));
+                }
+            }
+        }
+        tx.commit().context("Failed to write meetings database")?;
+        Ok(())
+    }
+
+    fn trash_meeting(&self, id: &str) -> Result<Meeting> {
+        let meeting = self
+            .query::<Meeting>("SELECT data FROM meetings WHERE id = ?", vec![Value::Text(id.to_string())])?
+            .pop()
+            .ok_or_else(|| anyhow!("No meeting with id {}", id))?;
+        let trashed = TrashedMeeting {
+            deleted_at: Local::now(),
+            meeting,
+        };
+        let data = serde_json::to_string(&trashed).context("Failed to serialize meeting")?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO trash (id, deleted_ms, data) VALUES (?1, ?2, ?3)",
+            params![id, trashed.deleted_at.timestamp_millis(), data],
+        )?;
+        tx.execute("DELETE FROM meetings WHERE id = ?1", params![id])?;
+        tx.commit().context("Failed to write meetings database")?;
+
+        Ok(trashed.meeting)
+    }
+
+    fn load_trash(&self) -> Result<Vec<TrashedMeeting>> {
+        self.query("SELECT data FROM trash ORDER BY deleted_ms", Vec::new())
+    }
+
+    fn restore_meeting(&self, id: &str) -> Result<Meeting> {
+        let trashed = self
+            .query::<TrashedMeeting>("SELECT data FROM trash WHERE id = ?", vec![Value::Text(id.to_string())])?
+            .pop()
+            .ok_or_else(|| anyhow!("No meeting with id {} in the trash", id))?;
+        let meeting = trashed.meeting;
+        let data = serde_json::to_string(&meeting).context("Failed to serialize meeting")?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO meetings (id, start_ms, cost, data) VALUES (?1, ?2, ?3, ?4)",
+            params![meeting.id, meeting.start_time.timestamp_millis(), meeting.cost.to_f64(), data],
+        )?;
+        tx.execute("DELETE FROM trash WHERE id = ?1", params![id])?;
+        tx.commit().context("Failed to write meetings database")?;
+
+        Ok(meeting)
+    }
+
+    fn location(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    fn find_meetings(&self, filter: &MeetingFilter) -> Result<Vec<Meeting>> {
+        // The date range goes to SQLite so the index does the work. Cost,
+        // which needs converting from each meeting's currency, and role and
+        // notes matching happen on the decoded meetings.
+        let mut clauses = Vec::new();
+        let mut args = Vec::new();
+        if let Some(since) = filter.since {
+            clauses.push("start_ms >= ?");
+            args.push(Value::Integer(since.timestamp_millis()));
+        }
+        if let Some(until) = filter.until {
+            clauses.push("start_ms < ?");
+            args.push(Value::Integer(until.timestamp_millis()));
+        }
+
+        let mut sql = String::from("SELECT data FROM meetings");
+        if !clauses.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+        sql.push_str(" ORDER BY start_ms DESC");
+
+        Ok(self
+            .query(&sql, args)?
+            .into_iter()
+            .filter(|meeting| filter.matches(meeting))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Config, Role};
+    use crate::storage::MinCost;
+    use crate::money::Money;
+    use chrono::{Duration, TimeZone};
+    use std::collections::HashMap;
+
+    fn meeting_on(day: u32, cost: i64) -> Meeting {
+        let start = Local.with_ymd_and_hms(2026, 3, day, 9, 0, 0).unwrap();
+        let mut attendees = HashMap::new();
+        attendees.insert(Role::new("engineer"), 2);
+        Meeting::new(start, start + Duration::minutes(30), attendees, Money::dollars(cost))
+    }
+
+    #[test]
+    fn test_save_and_query_by_range() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        for day in 1..=5 {
+            store.save_meeting(&meeting_on(day, day as i64 * 10)).unwrap();
+        }
+
+        let filter = MeetingFilter {
+            since: Some(Local.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap()),
+            until: Some(Local.with_ymd_and_hms(2026, 3, 5, 0, 0, 0).unwrap()),
+            min_cost: Some(MinCost::new(Money::dollars(25), &Config::default())),
+            ..Default::default()
+        };
+        let found = store.find_meetings(&filter).unwrap();
+        let costs: Vec<Money> = found.iter().map(|m| m.cost).collect();
+        assert_eq!(costs, vec![Money::dollars(40), Money::dollars(30)]);
+
+        assert_eq!(store.load_all_meetings().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_schema_version_is_upgraded_and_newer_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", 2).unwrap();
+        let store = SqliteStore::init(conn, PathBuf::from(":memory:")).unwrap();
+        let version: u32 = store
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MEETINGS_SCHEMA_VERSION);
+
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", MEETINGS_SCHEMA_VERSION + 1).unwrap();
+        assert!(SqliteStore::init(conn, PathBuf::from(":memory:")).is_err());
+    }
+
+    #[test]
+    fn test_import_skips_existing_ids() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let meetings = vec![meeting_on(1, 10), meeting_on(2, 20)];
+
+        assert_eq!(store.import_meetings(&meetings).unwrap(), 2);
+        assert_eq!(store.import_meetings(&meetings).unwrap(), 0);
+        assert_eq!(store.load_all_meetings().unwrap().len(), 2);
+
+        let trash = vec![TrashedMeeting {
+            deleted_at: Local::now(),
+            meeting: meeting_on(3, 30),
+        }];
+        assert_eq!(store.import_trash(&trash).unwrap(), 1);
+        assert_eq!(store.import_trash(&trash).unwrap(), 0);
+        store.restore_meeting(&trash[0].meeting.id).unwrap();
+        assert_eq!(store.load_all_meetings().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_trash_and_restore() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let meeting = meeting_on(1, 10);
+        store.save_meeting(&meeting).unwrap();
+        store.save_meeting(&meeting_on(2, 20)).unwrap();
+
+        let prefix = &meeting.id[..8];
+        let found = store.find_by_id(prefix).unwrap();
+        store.trash_meeting(&found.id).unwrap();
+        assert_eq!(store.load_all_meetings().unwrap().len(), 1);
+        assert!(store.find_by_id(prefix).is_err());
+
+        let trash = store.load_trash().unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].meeting.id, meeting.id);
+
+        store.restore_meeting(&meeting.id).unwrap();
+        assert_eq!(store.load_all_meetings().unwrap().len(), 2);
+        assert!(store.load_trash().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_update_meeting() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let mut meeting = meeting_on(1, 10);
+        store.save_meeting(&meeting).unwrap();
+
+        meeting.cost = Money::dollars(99);
+        store.update_meeting(&meeting).unwrap();
+        assert_eq!(store.find_by_id(&meeting.id).unwrap().cost, Money::dollars(99));
+
+        let filter = MeetingFilter {
+            min_cost: Some(MinCost::new(Money::dollars(50), &Config::default())),
+            ..Default::default()
+        };
+        assert_eq!(store.find_meetings(&filter).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_update_meetings_is_all_or_nothing() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let mut first = meeting_on(1, 10);
+        let mut second = meeting_on(2, 20);
+        store.save_meeting(&first).unwrap();
+        store.save_meeting(&second).unwrap();
+
+        first.cost = Money::dollars(11);
+        second.cost = Money::dollars(22);
+        assert!(store.update_meetings(&[first.clone(), meeting_on(3, 30)]).is_err());
+        assert_eq!(store.find_by_id(&first.id).unwrap().cost, Money::dollars(10));
+
+        store.update_meetings(&[first.clone(), second.clone()]).unwrap();
+        assert_eq!(store.find_by_id(&first.id).unwrap().cost, Money::dollars(11));
+        assert_eq!(store.find_by_id(&second.id).unwrap().cost, Money::dollars(22));
+    }
+}
@@ -2,11 +2,13 @@
 //
 // This module is responsible for parsing time and duration strings
 // from user input into structured data that the application can use.
-// It handles various formats for absolute times (e.g., "2pm", "14:30")
+// It handles various formats for absolute times (e.g., "2pm", "14:30", "noon"),
+// calendar dates (e.g., "2026-01-24", "yesterday", "friday", "-2d"),
+// relative times (e.g., "30 minutes ago"), time ranges (e.g., "2pm-3:30pm")
 // and durations (e.g., "90m", "1.5h").
 
 use anyhow::{anyhow, Result};
-use chrono::{Duration, NaiveTime};
+use chrono::{DateTime, Datelike, Days, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -17,6 +19,14 @@ lazy_static! {
     static ref DURATION_RE: Regex = Regex::new(r"^(?i)(?:(\d+)\s*h)?\s*(?:(\d+)\s*m)?$").unwrap();
     // Handles "2pm", "2:30pm", "14:00"
     static ref TIME_RE: Regex = Regex::new(r"^(?i)(\d{1,2})(?::(\d{2}))?\s*(am|pm)?$").unwrap();
+    // Handles "-2d", "-1w", "3 days ago", "1 week ago"
+    static ref RELATIVE_DAYS_RE: Regex = Regex::new(r"^(?i)(?:-(\d+)\s*(d|w)|(\d+)\s*(d|days?|w|weeks?)\s+ago)$").unwrap();
+    // Splits "2026-10-14 14:30", "2026-10-14T14:30" or "yesterday 2pm" into date and time
+    static ref DATETIME_RE: Regex = Regex::new(r"^(?i)(\S+?)(?:T|\s+)(\d{1,2}(?::\d{2})?\s*(?:am|pm)?|noon|midnight)$").unwrap();
+    // Handles "30 minutes ago", "1h ago"
+    static ref AGO_RE: Regex = Regex::new(r"^(?i)(.+?)\s+ago$").unwrap();
+    // Separators between the two ends of a range: "-", en/em dashes and "to"
+    static ref RANGE_SEPARATOR_RE: Regex = Regex::new(r"(?i)\s*(?:-|–|—|\bto\b)\s*").unwrap();
 }
 
 /// Parses a duration string into a `chrono::Duration`.
@@ -25,15 +35,25 @@ lazy_static! {
 /// - Minutes only: "90m", "45m"
 /// - Hours only: "2h", "1.5h"
 /// - Combined: "1h30m", "2h15m"
+/// - Spelled out: "30 minutes", "2 hours"
 pub fn parse_duration(s: &str) -> Result<Duration> {
     let s = s.trim();
+    let spaced;
+    let s = if s.contains(' ') {
+        // humantime wants "30minutes"; accept "30 minutes" too.
+        spaced = s.split_whitespace().collect::<String>();
+        spaced.as_str()
+    } else {
+        s
+    };
 
     // Try parsing decimal hours first, e.g., "1.5h"
-    if let Some(caps) = DECIMAL_HOURS_RE.captures(s) {
-        if let Some(hours_str) = caps.get(1) {
-            let hours = hours_str.as_str().parse::<f64>()?;
-            return Ok(Duration::minutes((hours * 60.0).round() as i64));
-        }
+    if let Some(caps) = DECIMAL_HOURS_RE.captures(s)
+        && let Some(hours_str) = caps.get(1)
+    {
+        let hours = hours_str.as_str().parse::<f64>()?;
+        return Duration::try_minutes((hours * 60.0).round() as i64)
+            .ok_or_else(|| anyhow!("Duration too long: '{}'", s));
     }
 
     // Fallback to humantime for "1h 30m", "90m", etc.
@@ -46,7 +66,10 @@ pub fn parse_duration(s: &str) -> Result<Duration> {
                 let minutes = caps.get(2).map_or(0, |m| m.as_str().parse::<i64>().unwrap_or(0));
 
                 if hours > 0 || minutes > 0 {
-                    return Ok(Duration::hours(hours) + Duration::minutes(minutes));
+                    return Duration::try_hours(hours)
+                        .zip(Duration::try_minutes(minutes))
+                        .and_then(|(hours, minutes)| hours.checked_add(&minutes))
+                        .ok_or_else(|| anyhow!("Duration too long: '{}'", s));
                 }
             }
             Err(anyhow!("Invalid duration format: '{}'. Use formats like '1.5h', '90m', or '1h30m'.", s))
@@ -60,9 +83,15 @@ pub fn parse_duration(s: &str) -> Result<Duration> {
 /// Supports formats like:
 /// - 12-hour format: "2pm", "2:30pm", "11:45am"
 /// - 24-hour format: "14:00", "14:30"
+/// - Named times: "noon", "midnight"
 /// - Case insensitive and flexible with colons.
 pub fn parse_time(s: &str) -> Result<NaiveTime> {
     let s = s.trim();
+    match s.to_lowercase().as_str() {
+        "noon" => return Ok(NaiveTime::from_hms_opt(12, 0, 0).unwrap()),
+        "midnight" => return Ok(NaiveTime::MIN),
+        _ => {}
+    }
     let caps = TIME_RE.captures(s).ok_or_else(|| anyhow!("Invalid time format: '{}'. Use formats like '2pm', '14:30', or '9:15am'.", s))?;
 
     let mut hour = caps.get(1).unwrap().as_str().parse::<u32>()?;
@@ -73,15 +102,12 @@ pub fn parse_time(s: &str) -> Result<NaiveTime> {
     }
 
     match caps.get(3).map(|m| m.as_str().to_lowercase()) {
-        Some(ref am_pm) if am_pm == "pm" => {
-            if hour < 12 {
-                hour += 12;
-            }
+        Some(ref am_pm) if am_pm == "pm" && hour < 12 => {
+            hour += 12;
         }
-        Some(ref am_pm) if am_pm == "am" => {
-            if hour == 12 { // 12am is midnight
-                hour = 0;
-            }
+        // 12am is midnight
+        Some(ref am_pm) if am_pm == "am" && hour == 12 => {
+            hour = 0;
         }
         // No am/pm, assume 24-hour if hour > 12, otherwise assume it's valid as is
         _ => {}
@@ -94,6 +120,240 @@ pub fn parse_time(s: &str) -> Result<NaiveTime> {
     NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(|| anyhow!("Failed to construct time"))
 }
 
+/// Parses a calendar date string into a `chrono::NaiveDate`, relative to today.
+///
+/// See `parse_date_from` for the supported formats.
+pub fn parse_date(s: &str) -> Result<NaiveDate> {
+    parse_date_from(s, Local::now().date_naive())
+}
+
+/// Parses a calendar date string relative to `today`.
+///
+/// Supports formats like:
+/// - ISO dates: "2026-01-24"
+/// - Named days: "today", "yesterday"
+/// - Weekdays: "friday", "fri" (the most recent one, today included) and
+///   "last friday" (strictly before today)
+/// - Relative offsets: "-2d", "-1w", "3 days ago"
+pub fn parse_date_from(s: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let s = s.trim();
+    let lower = s.to_lowercase();
+    let invalid = || {
+        anyhow!(
+            "Invalid date: '{}'. Use formats like '2026-01-24', 'yesterday', 'friday' or '-2d'.",
+            s
+        )
+    };
+
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    match lower.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return days_before(today, 1),
+        _ => {}
+    }
+
+    if let Some(caps) = RELATIVE_DAYS_RE.captures(&lower) {
+        let (amount, unit) = match (caps.get(1), caps.get(2)) {
+            (Some(amount), Some(unit)) => (amount, unit),
+            _ => (caps.get(3).ok_or_else(invalid)?, caps.get(4).ok_or_else(invalid)?),
+        };
+        let amount = amount.as_str().parse::<u64>().map_err(|_| invalid())?;
+        let days = if unit.as_str().starts_with('w') {
+            amount.checked_mul(7).ok_or_else(invalid)?
+        } else {
+            amount
+        };
+        return days_before(today, days);
+    }
+
+    let (strictly_before, day_name) = match lower.strip_prefix("last ") {
+        Some(rest) => (true, rest.trim()),
+        None => (false, lower.as_str()),
+    };
+    let weekday = day_name.parse::<Weekday>().map_err(|_| invalid())?;
+    let mut back = (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    if strictly_before && back == 0 {
+        back = 7;
+    }
+    days_before(today, back as u64)
+}
+
+fn days_before(date: NaiveDate, days: u64) -> Result<NaiveDate> {
+    date.checked_sub_days(Days::new(days))
+        .ok_or_else(|| anyhow!("Date out of range: {} days before {}", days, date))
+}
+
+/// Parses a point in time, e.g. "2pm", "2026-10-14 14:30", "yesterday 2pm",
+/// "30 minutes ago" or "now". A bare time falls on `default_date`; dates and
+/// relative times are resolved against `now`.
+pub fn parse_datetime(s: &str, default_date: NaiveDate, now: NaiveDateTime) -> Result<NaiveDateTime> {
+    let s = s.trim();
+    let today = now.date();
+
+    if let Ok(time) = parse_time(s) {
+        return Ok(default_date.and_time(time));
+    }
+
+    if s.eq_ignore_ascii_case("now") {
+        return Ok(now);
+    }
+
+    if let Some(caps) = AGO_RE.captures(s)
+        && let Ok(offset) = parse_duration(&caps[1])
+    {
+        return now
+            .checked_sub_signed(offset)
+            .ok_or_else(|| anyhow!("Date out of range: {} before {}", caps[1].trim(), now));
+    }
+
+    let caps = DATETIME_RE.captures(s).ok_or_else(|| {
+        anyhow!(
+            "Invalid time: '{}'. Use formats like '2pm', '14:30' or '2026-10-14 14:30'.",
+            s
+        )
+    })?;
+    let date = parse_date_from(&caps[1], today)?;
+    let time = parse_time(&caps[2])?;
+    Ok(date.and_time(time))
+}
+
+/// Parses a start and end from one expression, e.g. "2pm-3:30pm",
+/// "14:00–15:00", "2026-10-14 9am to 10am" or "30 minutes ago - now".
+///
+/// A single point such as "2pm" or "45 minutes ago" runs until `now`. An
+/// end that would fall before the start, as in "11pm-1am", is taken to be on
+/// the following day.
+pub fn parse_time_range(
+    s: &str,
+    default_date: NaiveDate,
+    now: NaiveDateTime,
+) -> Result<(NaiveDateTime, NaiveDateTime)> {
+    let s = s.trim();
+
+    // Dates contain dashes too, so try every separator until both sides parse.
+    for sep in RANGE_SEPARATOR_RE.find_iter(s) {
+        let (left, right) = (&s[..sep.start()], &s[sep.end()..]);
+        if left.is_empty() || right.is_empty() {
+            continue;
+        }
+        let Ok(start) = parse_datetime(left, default_date, now) else {
+            continue;
+        };
+        // An end without a date belongs to the start's day.
+        let Ok(mut end) = parse_datetime(right, start.date(), now) else {
+            continue;
+        };
+        if end <= start && parse_time(right).is_ok() {
+            end += Duration::days(1);
+        }
+        return Ok((start, end));
+    }
+
+    let start = parse_datetime(s, default_date, now).map_err(|_| {
+        anyhow!(
+            "Invalid time range: '{}'. Use formats like '2pm-3:30pm', '14:00-15:00' or '30 minutes ago'.",
+            s
+        )
+    })?;
+    if start >= now {
+        return Err(anyhow!(
+            "'{}' is not in the past, so it needs an end time, e.g. '{}-3pm'.",
+            s,
+            s
+        ));
+    }
+    Ok((start, now))
+}
+
+/// Parses when someone was at a meeting running from `start` to `end`, e.g.
+/// "14:30-15:00". A single time such as "14:30" or "14:30-" runs until the
+/// end, and "-9:15" from the start. Bare times fall on the day the meeting
+/// started.
+pub fn parse_window(
+    s: &str,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+) -> Result<(DateTime<Local>, DateTime<Local>)> {
+    let s = s.trim();
+    let date = start.date_naive();
+    let now = end.naive_local();
+    let at = |s: &str| parse_datetime(s, date, now).and_then(to_local);
+
+    if let Some(until) = s.strip_prefix('-') {
+        return Ok((start, at(until)?));
+    }
+    if let Some(from) = s.strip_suffix('-') {
+        return Ok((at(from)?, end));
+    }
+    if let Ok(from) = at(s) {
+        return Ok((from, end));
+    }
+    let (joined, left) = parse_time_range(s, date, now).map_err(|_| {
+        anyhow!(
+            "Invalid attendance time: '{}'. Use formats like '14:30-15:00', '14:30' (until the end) or '-9:15' (from the start).",
+            s
+        )
+    })?;
+    Ok((to_local(joined)?, to_local(left)?))
+}
+
+/// `at` moved by `offset`, which may be negative, failing instead of
+/// overflowing when that falls outside the dates chrono can represent.
+pub fn shift(at: DateTime<Local>, offset: Duration) -> Result<DateTime<Local>> {
+    at.checked_add_signed(offset)
+        .ok_or_else(|| anyhow!("Date out of range: too far from {}", at.format("%Y-%m-%d")))
+}
+
+/// Resolves a local wall-clock time, picking the earlier instant when a DST
+/// change makes it ambiguous.
+pub fn to_local(naive: NaiveDateTime) -> Result<DateTime<Local>> {
+    naive
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| anyhow!("{} does not exist in the local timezone", naive))
+}
+
+/// Resolves local midnight at the start of `date`.
+pub fn start_of_day(date: NaiveDate) -> Result<DateTime<Local>> {
+    to_local(date.and_time(NaiveTime::MIN))
+}
+
+/// Optional start (inclusive) and end (exclusive) of a date filter.
+pub type DateBounds = (Option<DateTime<Local>>, Option<DateTime<Local>>);
+
+/// Parses `--since`/`--until` bounds into a half-open range of instants.
+/// `until` is inclusive of the whole day, so its cut-off is the following
+/// midnight.
+pub fn parse_date_bounds(
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<DateBounds> {
+    let since = since
+        .map(|s| parse_date(s).and_then(start_of_day))
+        .transpose()?;
+    let until = until
+        .map(|s| {
+            parse_date(s).and_then(|date| {
+                let next = date
+                    .checked_add_days(Days::new(1))
+                    .ok_or_else(|| anyhow!("Date out of range: {}", date))?;
+                start_of_day(next)
+            })
+        })
+        .transpose()?;
+
+    if let (Some(since), Some(until)) = (since, until)
+        && until <= since
+    {
+        return Err(anyhow!("--until must not be before --since."));
+    }
+
+    Ok((since, until))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,6 +376,7 @@ mod tests {
     fn test_parse_duration_from_humantime() {
         assert_eq!(parse_duration("90m").unwrap(), Duration::minutes(90));
         assert_eq!(parse_duration("1m").unwrap(), Duration::minutes(1));
+        assert!(parse_duration("99999999999999999h").is_err());
     }
 
     #[test]
@@ -127,6 +388,126 @@ mod tests {
         assert_eq!(parse_time("12am").unwrap(), NaiveTime::from_hms_opt(0, 0, 0).unwrap());
     }
 
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("2026-01-24").unwrap(), ymd(2026, 1, 24));
+        assert!(parse_date("01/24/2026").is_err());
+    }
+
+    #[test]
+    fn test_parse_date_relative() {
+        // 2026-10-14 is a Wednesday.
+        let today = ymd(2026, 10, 14);
+        assert_eq!(parse_date_from("today", today).unwrap(), today);
+        assert_eq!(parse_date_from("Yesterday", today).unwrap(), ymd(2026, 10, 13));
+        assert_eq!(parse_date_from("-2d", today).unwrap(), ymd(2026, 10, 12));
+        assert_eq!(parse_date_from("-1w", today).unwrap(), ymd(2026, 10, 7));
+        assert_eq!(parse_date_from("3 days ago", today).unwrap(), ymd(2026, 10, 11));
+        assert_eq!(parse_date_from("friday", today).unwrap(), ymd(2026, 10, 9));
+        assert_eq!(parse_date_from("mon", today).unwrap(), ymd(2026, 10, 12));
+        assert_eq!(parse_date_from("wednesday", today).unwrap(), today);
+        assert_eq!(parse_date_from("last wednesday", today).unwrap(), ymd(2026, 10, 7));
+        assert!(parse_date_from("someday", today).is_err());
+        assert!(parse_date_from("-3000000000000000000w", today).is_err());
+    }
+
+    fn at(d: NaiveDate, h: u32, m: u32) -> NaiveDateTime {
+        d.and_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_datetime() {
+        let today = ymd(2026, 10, 14);
+        let now = at(today, 16, 45);
+
+        assert_eq!(parse_datetime("2pm", today, now).unwrap(), at(today, 14, 0));
+        assert_eq!(
+            parse_datetime("2pm", ymd(2026, 10, 9), now).unwrap(),
+            at(ymd(2026, 10, 9), 14, 0)
+        );
+        assert_eq!(
+            parse_datetime("2026-10-01 14:30", today, now).unwrap(),
+            at(ymd(2026, 10, 1), 14, 30)
+        );
+        assert_eq!(
+            parse_datetime("2026-10-01T9:15am", today, now).unwrap(),
+            at(ymd(2026, 10, 1), 9, 15)
+        );
+        assert_eq!(
+            parse_datetime("yesterday 4pm", today, now).unwrap(),
+            at(ymd(2026, 10, 13), 16, 0)
+        );
+        assert_eq!(
+            parse_datetime("yesterday noon", today, now).unwrap(),
+            at(ymd(2026, 10, 13), 12, 0)
+        );
+        assert!(parse_datetime("2026-10-01 25:00", today, now).is_err());
+    }
+
+    #[test]
+    fn test_parse_named_and_relative_times() {
+        let today = ymd(2026, 10, 14);
+        let now = at(today, 16, 45);
+
+        assert_eq!(parse_time("noon").unwrap(), NaiveTime::from_hms_opt(12, 0, 0).unwrap());
+        assert_eq!(parse_time("Midnight").unwrap(), NaiveTime::MIN);
+        assert_eq!(parse_datetime("now", today, now).unwrap(), now);
+        assert_eq!(parse_datetime("30 minutes ago", today, now).unwrap(), at(today, 16, 15));
+        assert_eq!(parse_datetime("1h ago", today, now).unwrap(), at(today, 15, 45));
+        assert_eq!(parse_datetime("2 hours ago", today, now).unwrap(), at(today, 14, 45));
+        assert!(parse_datetime("999999999999h ago", today, now).is_err());
+    }
+
+    #[test]
+    fn test_parse_time_range() {
+        let today = ymd(2026, 10, 14);
+        let now = at(today, 16, 45);
+        let range = |s| parse_time_range(s, today, now).unwrap();
+
+        assert_eq!(range("2pm-3:30pm"), (at(today, 14, 0), at(today, 15, 30)));
+        assert_eq!(range("14:00–15:00"), (at(today, 14, 0), at(today, 15, 0)));
+        assert_eq!(range("9am to noon"), (at(today, 9, 0), at(today, 12, 0)));
+        assert_eq!(range("2026-10-01 9am - 10am"), (at(ymd(2026, 10, 1), 9, 0), at(ymd(2026, 10, 1), 10, 0)));
+        assert_eq!(range("2026-10-01 14:00-2026-10-01 15:00"), (at(ymd(2026, 10, 1), 14, 0), at(ymd(2026, 10, 1), 15, 0)));
+        assert_eq!(range("11pm-1am"), (at(today, 23, 0), at(ymd(2026, 10, 15), 1, 0)));
+        assert_eq!(range("30 minutes ago - now"), (at(today, 16, 15), now));
+        assert_eq!(range("45 minutes ago"), (at(today, 16, 0), now));
+        assert_eq!(range("2pm"), (at(today, 14, 0), now));
+
+        assert!(parse_time_range("5pm", today, now).is_err());
+        assert!(parse_time_range("whenever", today, now).is_err());
+    }
+
+    #[test]
+    fn test_parse_window() {
+        let today = ymd(2026, 10, 14);
+        let local = |hour, minute| to_local(at(today, hour, minute)).unwrap();
+        let (start, end) = (local(14, 0), local(15, 0));
+        let window = |s| parse_window(s, start, end).unwrap();
+
+        assert_eq!(window("14:30-15:00"), (local(14, 30), end));
+        assert_eq!(window("2:15pm to 2:45pm"), (local(14, 15), local(14, 45)));
+        assert_eq!(window("14:30"), (local(14, 30), end));
+        assert_eq!(window("14:30-"), (local(14, 30), end));
+        assert_eq!(window("-14:20"), (start, local(14, 20)));
+
+        assert!(parse_window("later", start, end).is_err());
+    }
+
+    #[test]
+    fn test_parse_date_bounds() {
+        let (since, until) = parse_date_bounds(Some("2026-10-01"), Some("2026-10-01")).unwrap();
+        assert_eq!(since.unwrap(), start_of_day(ymd(2026, 10, 1)).unwrap());
+        assert_eq!(until.unwrap(), start_of_day(ymd(2026, 10, 2)).unwrap());
+
+        assert_eq!(parse_date_bounds(None, None).unwrap(), (None, None));
+        assert!(parse_date_bounds(Some("2026-10-02"), Some("2026-10-01")).is_err());
+    }
+
     #[test]
     fn test_parse_time_24hr() {
         assert_eq!(parse_time("14:00").unwrap(), NaiveTime::from_hms_opt(14, 0, 0).unwrap());
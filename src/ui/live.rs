@@ -12,38 +12,374 @@ use crossterm::{
     ExecutableCommand,
 };
 use ratatui::{
-    prelude::{CrosstermBackend, Constraint, Direction, Layout, Terminal},
-    widgets::{block::Title, Block, BorderType, Borders, Paragraph},
+    prelude::{CrosstermBackend, Constraint, Direction, Layout, Rect, Terminal},
+    widgets::{block::Title, Block, BorderType, Borders, Clear, Paragraph},
 };
 
-use crate::models::{Meeting, Role};
+use crate::calculator::Calculator;
+use crate::helpers::{display_cost, display_person_rate, display_rate};
+use crate::models::{Attendance, Config, Meeting, Participant, Pause, Role};
+use crate::money::Money;
+
+/// One head who arrived after the start or left before the end, in seconds
+/// of elapsed meeting time.
+#[derive(Debug, Clone)]
+pub struct Stint {
+    pub role: Role,
+    /// Set for a named person.
+    pub name: Option<String>,
+    pub joined: i64,
+    pub left: Option<i64>,
+}
+
+/// A line of the attendee list that arrivals and departures apply to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Row {
+    Role(Role),
+    Person(String),
+}
+
+/// Someone arriving or leaving, `at` seconds of elapsed meeting time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub at: i64,
+    pub row: Row,
+    pub arrived: bool,
+}
+
+/// The role picker: every configured role, to add or remove attendees of
+/// any of them.
+#[derive(Debug, Clone, Default)]
+pub struct Picker {
+    pub selected: usize,
+}
 
 /// Represents the state of the live meeting TUI.
 pub struct LiveMeetingState {
     pub start_time: DateTime<Local>,
+    /// Wall-clock seconds since the start, pauses included.
     pub elapsed_seconds: i64,
-    pub paused: bool,
-    pub pause_elapsed: i64, // Seconds elapsed when paused
+    /// When the meter was paused and resumed, in elapsed seconds; the last
+    /// pause is still open while the meter is paused.
+    pub pauses: Vec<(i64, Option<i64>)>,
+    /// Everyone who has attended so far, including those who have left.
     pub attendees: HashMap<Role, u32>,
-    pub cost_per_minute: f64,
-    pub config_rates: HashMap<Role, f64>,
+    /// Named attendees, also counted in `attendees`.
+    pub people: Vec<Participant>,
+    /// Heads who weren't there for the whole meeting so far.
+    pub stints: Vec<Stint>,
+    /// Arrivals and departures, oldest first.
+    pub changes: Vec<Change>,
+    /// Index into `rows()` of the selected line.
+    pub selected: usize,
+    /// Set while the role picker is open.
+    pub picker: Option<Picker>,
+    /// For whoever is in the room right now.
+    pub cost_per_minute: Money,
+    pub config: Config,
 }
 
 impl LiveMeetingState {
-    pub fn new(
-        attendees: HashMap<Role, u32>,
-        cost_per_minute: f64,
-        config_rates: HashMap<Role, f64>,
-    ) -> Self {
-        Self {
+    /// Fails if an attendee's role can't be priced.
+    pub fn new(attendees: HashMap<Role, u32>, people: Vec<Participant>, config: Config) -> Result<Self> {
+        let mut state = Self {
             start_time: Local::now(),
             elapsed_seconds: 0,
-            paused: false,
-            pause_elapsed: 0,
+            pauses: Vec::new(),
             attendees,
-            cost_per_minute,
-            config_rates,
+            people,
+            stints: Vec::new(),
+            changes: Vec::new(),
+            selected: 0,
+            picker: None,
+            cost_per_minute: Money::ZERO,
+            config,
+        };
+        state.update_rate()?;
+        Ok(state)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pauses.last().is_some_and(|(_, resumed)| resumed.is_none())
+    }
+
+    /// Pauses the meter, or resumes it if it is paused.
+    pub fn toggle_pause(&mut self) {
+        let now = self.elapsed_seconds;
+        match self.pauses.last_mut() {
+            Some((paused, resumed @ None)) => {
+                if *paused >= now {
+                    // Resumed straight away: no pause at all.
+                    self.pauses.pop();
+                } else {
+                    *resumed = Some(now);
+                }
+            }
+            _ => self.pauses.push((now, None)),
+        }
+    }
+
+    /// Seconds billed so far, i.e. not paused.
+    pub fn billed_seconds(&self) -> i64 {
+        let paused: i64 = self
+            .pauses
+            .iter()
+            .map(|(paused, resumed)| resumed.unwrap_or(self.elapsed_seconds) - paused)
+            .sum();
+        self.elapsed_seconds - paused
+    }
+
+    /// The attendee list: each role, then each named person.
+    pub fn rows(&self) -> Vec<Row> {
+        let mut roles: Vec<&Role> = self.attendees.keys().collect();
+        roles.sort();
+        roles
+            .into_iter()
+            .map(|role| Row::Role(role.clone()))
+            .chain(self.people.iter().map(|p| Row::Person(p.name.clone())))
+            .collect()
+    }
+
+    /// How many unnamed attendees of `role` are in the room.
+    pub fn present(&self, role: &Role) -> u32 {
+        let named = self.people.iter().filter(|p| &p.role == role).count() as u32;
+        let gone = self
+            .stints
+            .iter()
+            .filter(|s| s.name.is_none() && &s.role == role && s.left.is_some())
+            .count() as u32;
+        self.attendees
+            .get(role)
+            .copied()
+            .unwrap_or(0)
+            .saturating_sub(named + gone)
+    }
+
+    /// Whether the named attendee `name` is in the room.
+    pub fn is_present(&self, name: &str) -> bool {
+        self.stints
+            .iter()
+            .rfind(|s| s.name.as_deref() == Some(name))
+            .is_none_or(|s| s.left.is_none())
+    }
+
+    /// Records someone arriving now: another attendee of a role, or a named
+    /// person coming back.
+    pub fn arrive(&mut self, row: &Row) -> Result<()> {
+        let now = self.elapsed_seconds;
+        match row {
+            Row::Role(role) => {
+                *self.attendees.entry(role.clone()).or_insert(0) += 1;
+                self.stints.push(Stint {
+                    role: role.clone(),
+                    name: None,
+                    joined: now,
+                    left: None,
+                });
+            }
+            Row::Person(name) => {
+                if self.is_present(name) {
+                    return Ok(());
+                }
+                let Some(participant) = self.people.iter().find(|p| &p.name == name) else {
+                    return Ok(());
+                };
+                self.stints.push(Stint {
+                    role: participant.role.clone(),
+                    name: Some(name.clone()),
+                    joined: now,
+                    left: None,
+                });
+            }
+        }
+        self.changes.push(Change {
+            at: now,
+            row: row.clone(),
+            arrived: true,
+        });
+        self.update_rate()
+    }
+
+    /// Records someone leaving now: the latest arrival of a role still in
+    /// the room, or else one who was there from the start; or a named
+    /// person.
+    pub fn leave(&mut self, row: &Row) -> Result<()> {
+        let now = self.elapsed_seconds;
+        let (role, name) = match row {
+            Row::Role(role) => {
+                if self.present(role) == 0 {
+                    return Ok(());
+                }
+                (role.clone(), None)
+            }
+            Row::Person(name) => {
+                if !self.is_present(name) {
+                    return Ok(());
+                }
+                let Some(participant) = self.people.iter().find(|p| &p.name == name) else {
+                    return Ok(());
+                };
+                (participant.role.clone(), Some(name.clone()))
+            }
+        };
+
+        let open = self
+            .stints
+            .iter()
+            .rposition(|s| s.role == role && s.name == name && s.left.is_none());
+        match open {
+            // Arrived and left within the same second: as if they never came.
+            Some(i) if self.stints[i].joined >= now => {
+                self.stints.remove(i);
+                if name.is_none()
+                    && let Some(count) = self.attendees.get_mut(&role)
+                {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.attendees.remove(&role);
+                    }
+                }
+                if let Some(i) = self.changes.iter().rposition(|c| &c.row == row && c.arrived) {
+                    self.changes.remove(i);
+                }
+            }
+            Some(i) => {
+                self.stints[i].left = Some(now);
+                self.record_departure(row, now);
+            }
+            // Nobody can leave a meeting that hasn't started yet.
+            None if now == 0 => return Ok(()),
+            None => {
+                self.stints.push(Stint {
+                    role,
+                    name,
+                    joined: 0,
+                    left: Some(now),
+                });
+                self.record_departure(row, now);
+            }
+        }
+        self.selected = self.selected.min(self.rows().len().saturating_sub(1));
+        self.update_rate()
+    }
+
+    fn record_departure(&mut self, row: &Row, now: i64) {
+        self.changes.push(Change {
+            at: now,
+            row: row.clone(),
+            arrived: false,
+        });
+    }
+
+    /// The row `+` and `-` apply to: the role highlighted in the picker if
+    /// it is open, or else the selected line.
+    pub fn target(&self) -> Option<Row> {
+        match &self.picker {
+            Some(picker) => self
+                .config
+                .roles
+                .get(picker.selected)
+                .map(|role| Row::Role(role.id.clone())),
+            None => self.rows().get(self.selected).cloned(),
+        }
+    }
+
+    /// Moves the picker's or the attendee list's selection by `step`.
+    pub fn select(&mut self, step: isize) {
+        let (selected, len) = match &mut self.picker {
+            Some(picker) => (&mut picker.selected, self.config.roles.len()),
+            None => (&mut self.selected, self.attendees.len() + self.people.len()),
+        };
+        *selected = selected
+            .saturating_add_signed(step)
+            .min(len.saturating_sub(1));
+    }
+
+    /// Recomputes the cost per minute for whoever is in the room.
+    fn update_rate(&mut self) -> Result<()> {
+        let attendees: HashMap<Role, u32> = self
+            .attendees
+            .keys()
+            .map(|role| {
+                let named = self
+                    .people
+                    .iter()
+                    .filter(|p| &p.role == role && self.is_present(&p.name))
+                    .count() as u32;
+                (role.clone(), self.present(role) + named)
+            })
+            .collect();
+        let people: Vec<Participant> = self
+            .people
+            .iter()
+            .filter(|p| self.is_present(&p.name))
+            .cloned()
+            .collect();
+        self.cost_per_minute = Calculator::new(&self.config).cost_per_minute(&attendees, &people)?;
+        Ok(())
+    }
+
+    /// Brings `elapsed_seconds` up to the wall clock.
+    pub fn update_elapsed(&mut self) {
+        self.elapsed_seconds = (Local::now() - self.start_time).num_seconds();
+    }
+
+    /// The meeting so far, with the attendance of everyone who came late or
+    /// left early and when the meter was paused.
+    pub fn meeting(&self) -> Meeting {
+        self.meeting_until(self.start_time + chrono::Duration::seconds(self.elapsed_seconds))
+    }
+
+    /// The meeting ended now, to the moment rather than the last tick.
+    pub fn finish(&mut self) -> Meeting {
+        self.update_elapsed();
+        self.meeting_until(Local::now())
+    }
+
+    /// The meeting ending at `end_time`: anyone still in the room leaves
+    /// then, and an open pause ends then.
+    fn meeting_until(&self, end_time: DateTime<Local>) -> Meeting {
+        let at = |seconds| self.start_time + chrono::Duration::seconds(seconds);
+        let mut attendees = self.attendees.clone();
+        let mut attendance = Vec::new();
+        for stint in &self.stints {
+            let (joined, left) = (at(stint.joined), stint.left.map_or(end_time, at));
+            if left <= joined {
+                // Only just arrived, so not billed yet.
+                if stint.name.is_none()
+                    && let Some(count) = attendees.get_mut(&stint.role)
+                {
+                    *count -= 1;
+                }
+                continue;
+            }
+            attendance.push(Attendance {
+                role: stint.role.clone(),
+                count: 1,
+                name: stint.name.clone(),
+                joined,
+                left,
+            });
         }
+        let pauses = self
+            .pauses
+            .iter()
+            .map(|(paused, resumed)| (at(*paused), resumed.map_or(end_time, at)))
+            .filter(|(paused, resumed)| resumed > paused)
+            .map(|(paused, resumed)| Pause { paused, resumed })
+            .collect();
+
+        attendees.retain(|_, count| *count > 0);
+        let mut meeting = Meeting::new(self.start_time, end_time, attendees, Money::ZERO);
+        meeting.people = self.people.clone();
+        meeting.attendance = attendance;
+        meeting.pauses = pauses;
+        meeting
+    }
+
+    /// Cost so far, including the context switch every attendee pays up front.
+    pub fn current_cost(&self) -> Result<Money> {
+        Calculator::new(&self.config).meeting_cost(&self.meeting())
     }
 }
 
@@ -56,9 +392,10 @@ pub fn run_tui(mut state: LiveMeetingState) -> Result<Option<Meeting>> {
     let mut last_tick = Instant::now();
     let tick_rate = Duration::from_secs(1);
 
-    let mut return_value = None;
+    let return_value;
 
     loop {
+        let current_cost = state.current_cost()?;
         terminal.draw(|frame| {
             let full_area = frame.size();
 
@@ -89,16 +426,16 @@ pub fn run_tui(mut state: LiveMeetingState) -> Result<Option<Meeting>> {
                 ])
                 .split(outer_layout[0]);
 
-            let elapsed_time = format!(
-                "Elapsed Time: {:02}:{:02}:{:02}",
-                state.elapsed_seconds / 3600,
-                (state.elapsed_seconds / 60) % 60,
-                state.elapsed_seconds % 60
-            );
+            let mut elapsed_time = format!("Elapsed Time: {}", display_clock(state.billed_seconds()));
+            if !state.pauses.is_empty() {
+                elapsed_time.push_str(&format!("  (wall clock {})", display_clock(state.elapsed_seconds)));
+            }
+            if state.is_paused() {
+                elapsed_time.push_str("  PAUSED");
+            }
 
-            let current_cost = state.cost_per_minute * state.elapsed_seconds as f64 / 60.0;
-            let current_cost_str = format!("Current Cost:   ${:.2}", current_cost);
-            let cost_per_minute_str = format!("Cost/Minute:    ${:.2}", state.cost_per_minute);
+            let current_cost_str = format!("Current Cost:   {}", display_cost(current_cost, &state.config));
+            let cost_per_minute_str = format!("Cost/Minute:    {}", display_cost(state.cost_per_minute, &state.config));
 
             let time_cost_content = format!("{}\n\n{}\n{}", elapsed_time, current_cost_str, cost_per_minute_str);
             let time_cost_paragraph = Paragraph::new(time_cost_content);
@@ -111,18 +448,85 @@ pub fn run_tui(mut state: LiveMeetingState) -> Result<Option<Meeting>> {
                 .padding(ratatui::widgets::Padding::new(1, 0, 0, 0));
 
             let mut attendees_text = String::new();
-            for (role, count) in state.attendees.iter() {
-                let rate = state.config_rates.get(role).unwrap_or(&0.0);
-                attendees_text.push_str(&format!("  {}x {:?}          @ ${:.2}/hr\n", count, role, rate));
+            for (i, row) in state.rows().iter().enumerate() {
+                let marker = if i == state.selected { '›' } else { ' ' };
+                match row {
+                    Row::Role(role) => attendees_text.push_str(&format!(
+                        "{} {}x {:<18} @ {}\n",
+                        marker,
+                        state.present(role),
+                        state.config.role_name(role),
+                        display_rate(role, &state.config)
+                    )),
+                    Row::Person(name) => {
+                        let Some(participant) = state.people.iter().find(|p| &p.name == name) else {
+                            continue;
+                        };
+                        let rate = match state.config.person(name) {
+                            Some(person) => display_person_rate(person, &state.config),
+                            None => display_rate(&participant.role, &state.config),
+                        };
+                        let away = if state.is_present(name) { "" } else { "  (left)" };
+                        attendees_text.push_str(&format!(
+                            "{}    {:<18} @ {}{}\n",
+                            marker, name, rate, away
+                        ));
+                    }
+                }
             }
             let attendees_paragraph = Paragraph::new(attendees_text).block(attendees_block);
-            frame.render_widget(attendees_paragraph, outer_layout[1]);
+
+            if state.changes.is_empty() {
+                frame.render_widget(attendees_paragraph, outer_layout[1]);
+            } else {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(0), Constraint::Length(32)])
+                    .split(outer_layout[1]);
+                frame.render_widget(attendees_paragraph, columns[0]);
+
+                let changes_block = Block::default()
+                    .title("Changes:")
+                    .borders(Borders::TOP)
+                    .border_type(BorderType::Plain);
+                // Keep the latest changes in view.
+                let shown = changes_block.inner(columns[1]).height as usize;
+                let changes_text: String = state.changes[state.changes.len().saturating_sub(shown)..]
+                    .iter()
+                    .map(|change| format!("{}\n", display_change(change, &state)))
+                    .collect();
+                frame.render_widget(Paragraph::new(changes_text).block(changes_block), columns[1]);
+            }
+
+            if let Some(picker) = &state.picker {
+                let roles = &state.config.roles;
+                let area = centered(inner_area, 50, roles.len() as u16 + 2);
+                let mut picker_text = String::new();
+                for (i, role) in roles.iter().enumerate() {
+                    picker_text.push_str(&format!(
+                        "{} {:>2}x {:<22} @ {}\n",
+                        if i == picker.selected { '›' } else { ' ' },
+                        state.present(&role.id),
+                        role.name,
+                        display_rate(&role.id, &state.config)
+                    ));
+                }
+                let picker_block = Block::default()
+                    .title(" Add or remove attendees ")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded);
+                frame.render_widget(Clear, area);
+                frame.render_widget(Paragraph::new(picker_text).block(picker_block), area);
+            }
 
             let footer_block = Block::default()
                 .borders(Borders::TOP)
                 .border_type(BorderType::Plain);
-            let footer_text =
-                "[Space] Pause/Resume  [Q] Quit & Save  [Esc] Quit";
+            let footer_text = if state.picker.is_some() {
+                "[↑↓] Select role  [+] Add one  [-] Remove one  [Enter/Esc] Done"
+            } else {
+                "[↑↓] Select  [+] Arrived  [-] Left  [A] Roles  [Space] Pause/Resume  [Q] Quit & Save  [Esc] Quit"
+            };
             let footer_paragraph = Paragraph::new(footer_text).block(footer_block);
             frame.render_widget(footer_paragraph, outer_layout[2]);
         })?;
@@ -131,44 +535,49 @@ pub fn run_tui(mut state: LiveMeetingState) -> Result<Option<Meeting>> {
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
 
-        if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Char('Q') => {
-                            let end_time = state.start_time + chrono::Duration::seconds(state.elapsed_seconds);
-                            let total_cost = state.cost_per_minute * state.elapsed_seconds as f64 / 60.0;
-                            let meeting = Meeting::new(
-                                state.start_time,
-                                end_time,
-                                state.attendees.clone(),
-                                total_cost,
-                            );
-                            return_value = Some(meeting);
-                            break;
-                        }
-                        KeyCode::Esc => {
-                            return_value = None;
-                            break;
-                        }
-                        KeyCode::Char(' ') => {
-                            state.paused = !state.paused;
-                            if state.paused {
-                                state.pause_elapsed = state.elapsed_seconds;
-                            } else {
-                                state.start_time += chrono::Duration::seconds(state.elapsed_seconds - state.pause_elapsed);
-                            }
-                        }
-                        _ => {}
+        if crossterm::event::poll(timeout)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            // Arrivals, departures and pauses happen now, not at the last tick.
+            state.update_elapsed();
+            match key.code {
+                // The picker takes Enter and Esc, so Esc there doesn't discard the meeting.
+                KeyCode::Enter | KeyCode::Esc | KeyCode::Char('a') | KeyCode::Char('A')
+                    if state.picker.is_some() =>
+                {
+                    state.picker = None;
+                }
+                KeyCode::Char('q') | KeyCode::Char('Q') => {
+                    let mut meeting = state.finish();
+                    Calculator::new(&state.config).update_meeting_cost(&mut meeting)?;
+                    return_value = Some(meeting);
+                    break;
+                }
+                KeyCode::Esc => {
+                    return_value = None;
+                    break;
+                }
+                KeyCode::Up => state.select(-1),
+                KeyCode::Down => state.select(1),
+                KeyCode::Char('+') | KeyCode::Char('=') => {
+                    if let Some(row) = state.target() {
+                        state.arrive(&row)?;
+                    }
+                }
+                KeyCode::Char('-') => {
+                    if let Some(row) = state.target() {
+                        state.leave(&row)?;
                     }
                 }
+                KeyCode::Char('a') | KeyCode::Char('A') => state.picker = Some(Picker::default()),
+                KeyCode::Char(' ') => state.toggle_pause(),
+                _ => {}
             }
         }
 
         if last_tick.elapsed() >= tick_rate {
-            if !state.paused {
-                state.elapsed_seconds += 1;
-            }
+            state.update_elapsed();
             last_tick = Instant::now();
         }
     }
@@ -177,4 +586,117 @@ pub fn run_tui(mut state: LiveMeetingState) -> Result<Option<Meeting>> {
     disable_raw_mode()?;
 
     Ok(return_value)
-}
\ No newline at end of file
+}
+
+/// Seconds as "hh:mm:ss".
+fn display_clock(seconds: i64) -> String {
+    format!("{:02}:{:02}:{:02}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
+}
+
+/// One line of the change timeline, e.g. "2:31 PM  + Engineer".
+fn display_change(change: &Change, state: &LiveMeetingState) -> String {
+    let when = state.start_time + chrono::Duration::seconds(change.at);
+    let who = match &change.row {
+        Row::Role(role) => state.config.role_name(role),
+        Row::Person(name) => name.clone(),
+    };
+    format!(
+        "{:>8}  {} {}",
+        when.format("%-I:%M %p"),
+        if change.arrived { '+' } else { '-' },
+        who
+    )
+}
+
+/// A `width` by `height` area in the middle of `area`, shrunk to fit.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrivals_and_departures_are_costed_by_segment() {
+        let mut state = LiveMeetingState::new(
+            HashMap::from([(Role::new("engineer"), 2)]),
+            Vec::new(),
+            Config::default(),
+        )
+        .unwrap();
+        let engineer = Row::Role(Role::new("engineer"));
+        let pm = Row::Role(Role::new("product_manager"));
+        assert_eq!(state.cost_per_minute, Money::from_f64(86.0 / 60.0));
+
+        // A PM joins after 30 minutes and an engineer leaves after 45.
+        state.elapsed_seconds = 30 * 60;
+        state.arrive(&pm).unwrap();
+        assert_eq!(state.cost_per_minute, Money::from_f64(158.0 / 60.0));
+        state.elapsed_seconds = 45 * 60;
+        state.leave(&engineer).unwrap();
+        state.elapsed_seconds = 60 * 60;
+
+        assert_eq!(state.present(&Role::new("engineer")), 1);
+        assert_eq!(state.changes.len(), 2);
+        let meeting = state.meeting();
+        meeting.check_attendance().unwrap();
+        // $43 + $32.25 for the engineers, $36 for the PM.
+        assert_eq!(state.current_cost().unwrap(), Money::from_f64(111.25));
+
+        // Someone added by mistake and removed straight away leaves no trace.
+        state.arrive(&pm).unwrap();
+        state.leave(&pm).unwrap();
+        assert_eq!(state.meeting().attendees.get(&Role::new("product_manager")), Some(&1));
+        assert_eq!(state.changes.len(), 2);
+    }
+
+    #[test]
+    fn test_pauses_keep_the_real_start_and_end() {
+        let mut state = LiveMeetingState::new(
+            HashMap::from([(Role::new("engineer"), 1)]),
+            Vec::new(),
+            Config::default(),
+        )
+        .unwrap();
+        state.elapsed_seconds = 20 * 60;
+        state.toggle_pause();
+        assert!(state.is_paused());
+        state.elapsed_seconds = 30 * 60;
+        state.toggle_pause();
+        state.elapsed_seconds = 40 * 60;
+
+        assert_eq!(state.billed_seconds(), 30 * 60);
+        let meeting = state.meeting();
+        assert_eq!(meeting.start_time, state.start_time);
+        assert_eq!(meeting.wall_clock_duration(), chrono::Duration::minutes(40));
+        assert_eq!(meeting.billed_duration(), chrono::Duration::minutes(30));
+        assert_eq!(state.current_cost().unwrap(), Money::from_f64(21.5));
+    }
+
+    #[test]
+    fn test_finish_ends_at_the_wall_clock() {
+        let mut state = LiveMeetingState::new(
+            HashMap::from([(Role::new("engineer"), 1)]),
+            Vec::new(),
+            Config::default(),
+        )
+        .unwrap();
+        state.start_time = Local::now() - chrono::Duration::minutes(10);
+        state.elapsed_seconds = 5 * 60;
+        state.toggle_pause();
+
+        // The last tick was minutes ago; the meeting and its pause still end now.
+        let meeting = state.finish();
+        assert!(meeting.wall_clock_duration() >= chrono::Duration::minutes(10));
+        assert_eq!(meeting.pauses[0].resumed, meeting.end_time);
+        assert_eq!(meeting.billed_duration(), chrono::Duration::minutes(5));
+    }
+}